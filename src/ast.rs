@@ -1,6 +1,8 @@
 use std::rc::Rc;
 
 use crate::at_expression::AtExpToken;
+use crate::grammar::vector::Vector_;
+use crate::number_format::NumberFormat;
 
 use super::grammar;
 use super::typed_value::TypedValue;
@@ -9,6 +11,8 @@ use super::typed_value::TypedValue;
 pub struct Ast {
     pub root_node: Option<DefAstNode>,
     pub imported_values_count: usize,
+    /// Format of the result, e.g. `.3f` in `@(x / 3 : .3f)`.
+    pub number_format: Option<NumberFormat>,
 }
 
 impl Ast {
@@ -16,6 +20,7 @@ impl Ast {
         Ast {
             root_node: None,
             imported_values_count: 0,
+            number_format: None,
         }
     }
 
@@ -61,6 +66,7 @@ impl Ast {
         Ok(Ast {
             root_node,
             imported_values_count,
+            number_format: None,
         })
     }
 
@@ -77,10 +83,24 @@ impl Ast {
                 imported_values.len()
             ));
         }
-        match self.root_node {
-            Some(ref root) => Ok(root.evaluate(imported_values)?),
-            None => Err("Attempted to evaluate an empty AST".to_string()),
+        let value = match self.root_node {
+            Some(ref root) => root.evaluate(imported_values)?,
+            None => return Err("Attempted to evaluate an empty AST".to_string()),
+        };
+        match value {
+            TypedValue::Number(_)
+            | TypedValue::Vector(_)
+            | TypedValue::Matrix(_)
+            | TypedValue::Size(_) => {}
+            _ if self.number_format.is_some() => {
+                return Err(format!(
+                    "Can't apply a number format to {} in @() expression.",
+                    value.type_name()
+                ))
+            }
+            _ => {}
         }
+        Ok(value)
     }
 
     pub fn codegen(&self) -> String {
@@ -97,4 +117,12 @@ pub type DefAstNode = Rc<dyn AstNode>;
 pub trait AstNode {
     fn evaluate(&self, imported_values: &Vec<TypedValue>) -> Result<TypedValue, String>;
     fn codegen(&self) -> String;
+    /// Code without redundant outer brackets, used where TeX groups the result anyway.
+    fn codegen_ungrouped(&self) -> String {
+        self.codegen()
+    }
+    /// Vectors nested in a vector are stacked as the rows of a matrix.
+    fn as_vector(&self) -> Option<&Vector_> {
+        None
+    }
 }
@@ -1,18 +1,29 @@
 use crate::token::*;
 
 use super::ast::*;
+use super::builtins::constant;
+use super::colour::Colour;
+use super::data;
+use super::grammar::big_operator::{find_bound_variables, is_big_operator_name};
+use super::grammar::function_call::{function_names, is_function_name};
+use super::grammar::template_string::parse_string_literal;
+use super::number_format::NumberFormat;
+use super::size::Size;
 use super::typed_value::TypedValue;
+use crate::parse::CompilerGlobals;
 use crate::parser_modules::variables::var_definition::LiaVarName;
 
-static OPERATORS_AND_KEYWORDS: [&str; 15] = [
-    "+", "-", "*", "/", "%", "?", ":", "(", ")", "{", "}", "^", ",", "[", "]",
+static OPERATORS_AND_KEYWORDS: [&str; 25] = [
+    "+", "-", "*", "/", "%", "?", ":", "(", ")", "{", "}", "^", ",", "[", "]", ";", "==", "!=",
+    "<", "<=", ">", ">=", "and", "or", "not",
 ];
 
 pub fn parse_at_exprssion(
     tokens: &Vec<Token>,
     lia_variables: Vec<LiaVarName>,
+    globals: &CompilerGlobals,
 ) -> Result<Ast, String> {
-    let lia_variables = lia_variables
+    let lia_variables: Vec<LiaVarName> = lia_variables
         .into_iter()
         .filter(|v| {
             if let LiaVarName::Lamda(_) = v {
@@ -22,16 +33,92 @@ pub fn parse_at_exprssion(
             }
         })
         .collect();
+    // Errors of the parser don't know which token they are about, so they point at the expression.
+    let location = tokens
+        .first()
+        .map(|t| format!("{} ", t.get_location().stringify()))
+        .unwrap_or_default();
     let mut errors: Vec<String> = Vec::new();
     let mut did_error = false;
+    let tokens = join_data_paths(&split_operators(&join_hex_colours(tokens)), globals);
+    let (tokens, number_format) = split_number_format(&tokens)?;
+    let bound_variables = find_bound_variables(tokens);
     let tokens: Vec<AtExpToken> = tokens
         .iter()
-        .map(|t| match AtExpToken::tokenise(t, &lia_variables) {
-            Ok(t) => t,
-            Err(e) => {
-                errors.push(e);
-                did_error = true;
-                AtExpToken::Error
+        .enumerate()
+        .map(|(i, t)| {
+            // Variables bound by big operators are numbered after the imported values.
+            let innermost_bound = bound_variables
+                .iter()
+                .enumerate()
+                .rev()
+                .find(|(_, b)| b.scope.0 < i && i < b.scope.1 && t.stringify() == b.name);
+            if let Some((k, b)) = innermost_bound {
+                let index = lia_variables.len() + k;
+                return if b.declaration == i {
+                    AtExpToken::BoundIdentifier(index, b.name.clone())
+                } else {
+                    AtExpToken::Identifier(index)
+                };
+            }
+            let after_declaration = i > 0 && bound_variables.iter().any(|b| b.declaration == i - 1);
+            if after_declaration && matches!(t.stringify().as_str(), "=" | "->") {
+                return AtExpToken::OperatorOrKeyword(t.stringify());
+            }
+            let is_call = matches!(tokens.get(i + 1), Some(n) if n.stringify() == "(");
+            if is_call && is_big_operator_name(&t.stringify()) {
+                return AtExpToken::Text(t.stringify());
+            }
+            if is_call && is_function_name(&t.stringify()) {
+                return AtExpToken::OperatorOrKeyword(t.stringify());
+            }
+            let name = t.stringify();
+            if name.starts_with('"') {
+                return match parse_string_literal(&name, t.get_location(), &lia_variables, globals)
+                {
+                    Ok(t) => t,
+                    Err(e) => {
+                        errors.push(e);
+                        did_error = true;
+                        AtExpToken::Error
+                    }
+                };
+            }
+            if !lia_variables.iter().any(|v| v.matches_name(&name)) {
+                match data::lookup(&name, globals) {
+                    Some(Ok(value)) => return AtExpToken::Literal(value),
+                    Some(Err(e)) => {
+                        errors.push(format!("{} {}", t.get_location().stringify(), e));
+                        did_error = true;
+                        return AtExpToken::Error;
+                    }
+                    None => {}
+                }
+                if let Some(colour) = Colour::named(&name, &globals.colours) {
+                    return AtExpToken::Literal(TypedValue::Colour(colour));
+                }
+                if let Some(n) = constant(&name) {
+                    return AtExpToken::Literal(TypedValue::Number(n));
+                }
+                let is_name = name.chars().next().is_some_and(|c| c.is_alphabetic());
+                if is_call && is_name {
+                    errors.push(format!(
+                        "{} Unknown function \"{}\" in @() expression. Available functions are {}.",
+                        t.get_location().stringify(),
+                        name,
+                        function_names().join(", ")
+                    ));
+                    did_error = true;
+                    return AtExpToken::Error;
+                }
+            }
+            match AtExpToken::tokenise(t, &lia_variables) {
+                Ok(t) => t,
+                Err(e) => {
+                    errors.push(e);
+                    did_error = true;
+                    AtExpToken::Error
+                }
             }
         })
         .collect();
@@ -39,19 +126,142 @@ pub fn parse_at_exprssion(
         return Err(errors.join("\n").to_string());
     }
     if tokens.is_empty() {
-        return Err("Found empty @() expression.".to_string());
+        return Err(format!("{}Found empty @() expression.", location));
     }
-    let ast = Ast::construct(
+    let mut ast = Ast::construct(
         &tokens,
         lia_variables.len(),
         "Could not parse @() expression",
-    )?;
+    )
+    .map_err(|e| format!("{}{}", location, e))?;
+    ast.number_format = number_format;
     Ok(ast)
 }
 
+/// `#1f77b4` is split into `#` and the digits by the tokenizer.
+fn join_hex_colours(tokens: &[Token]) -> Vec<Token> {
+    let mut joined: Vec<Token> = Vec::new();
+    for token in tokens {
+        match (joined.last_mut(), token) {
+            (Some(Token::Misc(hash, _)), Token::Misc(digits, _))
+                if hash == "#" && Colour::from_hex(&format!("#{}", digits)).is_some() =>
+            {
+                hash.push_str(digits)
+            }
+            _ => joined.push(token.clone()),
+        }
+    }
+    joined
+}
+
+/// `data[2].voltage` is split by the tokenizer, it is joined back to be looked up as a whole.
+fn join_data_paths(tokens: &[Token], globals: &CompilerGlobals) -> Vec<Token> {
+    let mut joined: Vec<Token> = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let mut token = tokens[i].clone();
+        i += 1;
+        if let Token::Misc(path, _) = &mut token {
+            let root = &path[..path.find(['.', '[']).unwrap_or(path.len())];
+            let indexable = matches!(
+                globals.constant(root),
+                Some(
+                    TypedValue::List(_)
+                        | TypedValue::Record(_)
+                        | TypedValue::Vector(_)
+                        | TypedValue::Matrix(_)
+                )
+            );
+            if indexable {
+                loop {
+                    match (tokens.get(i), tokens.get(i + 1), tokens.get(i + 2)) {
+                        (
+                            Some(Token::Misc(open, _)),
+                            Some(Token::Misc(index, _)),
+                            Some(Token::Misc(close, _)),
+                        ) if open == "[" && close == "]" => {
+                            path.push_str(&format!("[{}]", index));
+                            i += 3;
+                        }
+                        (Some(Token::Misc(field, _)), _, _) if field.starts_with('.') => {
+                            path.push_str(field);
+                            i += 1;
+                        }
+                        _ => break,
+                    }
+                }
+            }
+        }
+        joined.push(token);
+    }
+    joined
+}
+
+/// Symbols are grouped by the tokenizer, so `x>-1` has the single token `>-`.
+fn split_operators(tokens: &[Token]) -> Vec<Token> {
+    let mut split = Vec::new();
+    for token in tokens {
+        let text = token.stringify();
+        if let (Token::Misc(_, loc), Some(operators)) = (token, split_symbol_run(&text)) {
+            split.extend(operators.into_iter().map(|op| Token::Misc(op, *loc)));
+        } else {
+            split.push(token.clone());
+        }
+    }
+    split
+}
+
+fn split_symbol_run(text: &str) -> Option<Vec<String>> {
+    if OPERATORS_AND_KEYWORDS.contains(&text) || text == "->" || !text.is_ascii() {
+        return None;
+    }
+    let mut operators = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let op = [2, 1]
+            .into_iter()
+            .filter(|len| *len <= rest.len())
+            .map(|len| &rest[..len])
+            .find(|op| OPERATORS_AND_KEYWORDS.contains(op))?;
+        operators.push(op.to_string());
+        rest = &rest[op.len()..];
+    }
+    Some(operators)
+}
+
+/// Splits off a number format after the last `:` that isn't part of a `? :`, e.g. `@(x / 3 : .3f)`.
+fn split_number_format(tokens: &[Token]) -> Result<(&[Token], Option<NumberFormat>), String> {
+    let mut depth = 0;
+    let mut questions = 0;
+    let mut colons = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token.stringify().as_str() {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => depth -= 1,
+            "?" if depth == 0 => questions += 1,
+            ":" if depth == 0 => colons.push(i),
+            _ => {}
+        }
+    }
+    match colons.last() {
+        Some(&colon) if colons.len() > questions => {
+            let spec = tokens[colon + 1..]
+                .iter()
+                .map(|t| t.stringify())
+                .collect::<String>();
+            let format = NumberFormat::parse(&spec)
+                .map_err(|e| format!("{} {}", tokens[colon].get_location().stringify(), e))?;
+            Ok((&tokens[..colon], Some(format)))
+        }
+        _ => Ok((tokens, None)),
+    }
+}
+
 #[derive(Clone)]
 pub enum AtExpToken {
     Identifier(usize),
+    /// Declaration of a variable bound by a big operator, e.g. `i` in `sum(i = 0, n, i)`.
+    BoundIdentifier(usize, String),
     Literal(TypedValue),
     OperatorOrKeyword(String),
     AstNode(DefAstNode),
@@ -83,12 +293,19 @@ impl AtExpToken {
         match token {
             Token::Misc(t, loc) => {
                 let first_char = t.chars().next().unwrap();
-                if first_char.is_numeric() {
+                if first_char.is_numeric() || first_char == '\\' {
                     return parse_numerical_literal(t.clone(), *loc);
-                } else if first_char == '"' {
-                    return Ok(AtExpToken::Literal(TypedValue::String(
-                        t[1..t.len() - 1].to_string(),
-                    )));
+                } else if first_char == '#' {
+                    return match Colour::from_hex(t) {
+                        Some(colour) => Ok(AtExpToken::Literal(TypedValue::Colour(colour))),
+                        None => Err(format!(
+                            "{} Invalid colour \"{}\" in @(), expected #RRGGBB.",
+                            loc.stringify(),
+                            t
+                        )),
+                    };
+                } else if let Ok(b) = t.parse::<bool>() {
+                    return Ok(AtExpToken::Literal(TypedValue::Bool(b)));
                 }
                 for op in OPERATORS_AND_KEYWORDS {
                     if t == op {
@@ -105,15 +322,27 @@ impl AtExpToken {
     }
 }
 
+/// A bare unit like `em` or `\textwidth` is only a size with a number in front, otherwise it
+/// is text unless it is passed as a `Size` argument.
 pub fn string_to_typed_value(s: String) -> Result<TypedValue, String> {
     if let Ok(n) = s.parse::<f64>() {
         Ok(TypedValue::Number(n))
+    } else if let Ok(b) = s.parse::<bool>() {
+        Ok(TypedValue::Bool(b))
+    } else if let Some(colour) = Colour::from_hex(&s) {
+        Ok(TypedValue::Colour(colour))
+    } else if let Some(size) = Size::parse(&s).filter(|_| s.contains(|c: char| c.is_ascii_digit()))
+    {
+        Ok(TypedValue::Size(size))
     } else {
         Ok(TypedValue::String(s))
     }
 }
 
 pub fn parse_numerical_literal(s: String, loc: Location) -> Result<AtExpToken, String> {
+    if let Some(size) = Size::parse(&s) {
+        return Ok(AtExpToken::Literal(TypedValue::Size(size)));
+    }
     match s.parse::<f64>() {
         Ok(n) => Ok(AtExpToken::Literal(TypedValue::Number(n))),
         Err(_) => Err(format!(
@@ -157,6 +386,7 @@ pub fn to_typed_var_name(
         "String" | "txt" => Ok(LiaVarName::String(name)),
         "Size" | "sz" => Ok(LiaVarName::Size(name)),
         "Colour" | "Color" | "col" => Ok(LiaVarName::Colour(name)),
+        "Bool" | "bool" => Ok(LiaVarName::Bool(name)),
         "Lamda" | "fn" | "λ" => Ok(LiaVarName::Lamda(Ast::default())),
         "Any" => Ok(LiaVarName::Any(name)),
         _ => {
@@ -164,3 +394,50 @@ pub fn to_typed_var_name(
         }
     }
 }
+
+/// Joins the tokens of a string literal in an @() expression, which the tokenizer splits at
+/// whitespace, brackets and symbols. `\"` doesn't end the literal.
+#[derive(Default)]
+pub struct StringLiteralLexer {
+    buffer: Option<(String, Location)>,
+    escaped: bool,
+}
+
+impl StringLiteralLexer {
+    /// Whether `token` is part of a string literal, either inside one or opening one.
+    pub fn accepts(&self, token: &Token) -> bool {
+        self.buffer.is_some() || matches!(token, Token::Misc(t, _) if t.starts_with('"'))
+    }
+
+    /// Where a literal that is still open started.
+    pub fn unclosed(&self) -> Option<Location> {
+        self.buffer.as_ref().map(|(_, location)| *location)
+    }
+
+    /// Returns the literal once it is closed, followed by any text after the closing quote.
+    pub fn feed(&mut self, token: &Token) -> Vec<Token> {
+        let text = token.stringify();
+        let mut chars = text.chars();
+        let (buffer, location) = self.buffer.get_or_insert_with(|| {
+            chars.next();
+            ("\"".to_string(), token.get_location())
+        });
+        for c in chars.by_ref() {
+            buffer.push(c);
+            if self.escaped {
+                self.escaped = false;
+            } else if c == '\\' {
+                self.escaped = true;
+            } else if c == '"' {
+                let mut closed = vec![Token::Misc(buffer.clone(), *location)];
+                let rest: String = chars.collect();
+                if !rest.is_empty() {
+                    closed.push(Token::Misc(rest, *location));
+                }
+                self.buffer = None;
+                return closed;
+            }
+        }
+        Vec::new()
+    }
+}
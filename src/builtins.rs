@@ -0,0 +1,118 @@
+use crate::typed_value::TypedValue;
+
+/// Constants available by name in @() expressions.
+pub static CONSTANTS: [(&str, f64); 2] = [("pi", std::f64::consts::PI), ("e", std::f64::consts::E)];
+
+pub fn constant(name: &str) -> Option<f64> {
+    CONSTANTS.iter().find(|(c, _)| *c == name).map(|(_, v)| *v)
+}
+
+/// Evaluates the math and string functions of @() expressions. Arity is checked when parsing.
+pub fn call(name: &str, args: &[TypedValue]) -> Result<TypedValue, String> {
+    let number = |i: usize| match &args[i] {
+        TypedValue::Number(n) => Ok(*n),
+        value => Err(argument_error(name, i, "Number", value)),
+    };
+    let string = |i: usize| match &args[i] {
+        TypedValue::String(s) => Ok(s.clone()),
+        value => Err(argument_error(name, i, "String", value)),
+    };
+    let index = |i: usize| -> Result<usize, String> {
+        let n = number(i)?;
+        if n < 0.0 || n.fract() != 0.0 {
+            return Err(format!(
+                "Argument {} of {}() in @() expression must be a whole number, found {}.",
+                i + 1,
+                name,
+                n
+            ));
+        }
+        Ok(n as usize)
+    };
+    let unary = |f: fn(f64) -> f64| Ok(TypedValue::Number(f(number(0)?)));
+    match name {
+        "sin" => unary(f64::sin),
+        "cos" => unary(f64::cos),
+        "tan" => unary(f64::tan),
+        "asin" => unary(f64::asin),
+        "acos" => unary(f64::acos),
+        "atan" => unary(f64::atan),
+        "exp" => unary(f64::exp),
+        "floor" => unary(f64::floor),
+        "ceil" => unary(f64::ceil),
+        "log" => match args.len() {
+            1 => unary(f64::ln),
+            // log10 and log2 are exact for powers of their base.
+            _ => Ok(TypedValue::Number(match number(1)? {
+                10.0 => number(0)?.log10(),
+                2.0 => number(0)?.log2(),
+                base => number(0)?.log(base),
+            })),
+        },
+        "round" => {
+            let scale = match args.len() {
+                1 => 1.0,
+                _ => 10f64.powi(index(1)? as i32),
+            };
+            Ok(TypedValue::Number((number(0)? * scale).round() / scale))
+        }
+        "min" | "max" => {
+            let mut values = Vec::new();
+            for (i, arg) in args.iter().enumerate() {
+                match arg {
+                    TypedValue::Number(n) => values.push(*n),
+                    TypedValue::Vector(v) => values.extend(v),
+                    value => return Err(argument_error(name, i, "Number", value)),
+                }
+            }
+            let extreme = match name {
+                "min" => values.into_iter().reduce(f64::min),
+                _ => values.into_iter().reduce(f64::max),
+            };
+            extreme
+                .map(TypedValue::Number)
+                .ok_or_else(|| format!("{}() of an empty vector in @() expression.", name))
+        }
+        "upper" => Ok(TypedValue::String(string(0)?.to_uppercase())),
+        "lower" => Ok(TypedValue::String(string(0)?.to_lowercase())),
+        "len" => match &args[0] {
+            TypedValue::String(s) => Ok(TypedValue::Number(s.chars().count() as f64)),
+            TypedValue::Vector(v) => Ok(TypedValue::Number(v.len() as f64)),
+            TypedValue::List(items) => Ok(TypedValue::Number(items.len() as f64)),
+            value => Err(argument_error(name, 0, "String", value)),
+        },
+        "replace" => Ok(TypedValue::String(
+            string(0)?.replace(&string(1)?, &string(2)?),
+        )),
+        "substr" => {
+            let s = string(0)?;
+            let start = index(1)?;
+            let length = match args.len() {
+                2 => usize::MAX,
+                _ => index(2)?,
+            };
+            Ok(TypedValue::String(
+                s.chars().skip(start).take(length).collect(),
+            ))
+        }
+        "join" => {
+            let separator = string(0)?;
+            let parts = args[1..]
+                .iter()
+                .map(|a| a.stringify())
+                .collect::<Vec<String>>();
+            Ok(TypedValue::String(parts.join(&separator)))
+        }
+        _ => Err(format!("Unknown function \"{}\" in @() expression.", name)),
+    }
+}
+
+pub fn argument_error(function: &str, i: usize, expected: &str, found: &TypedValue) -> String {
+    format!(
+        "Argument {} of {}() in @() expression must be a {}, found {}.",
+        i + 1,
+        function,
+        expected,
+        found.type_name()
+    )
+}
@@ -13,6 +13,10 @@ pub fn print_info(msg: String) {
     println!("[{}] {}", "INFO".yellow(), msg);
 }
 
+pub fn print_warning(msg: String) {
+    println!("[{}] {}", "WARNING".yellow(), msg);
+}
+
 fn parse_flag(flag: &str) -> Result<Flag, String> {
     // TODO: Compound small flags. Not needed for now.
     match flag {
@@ -45,6 +49,15 @@ fn parse_flag(flag: &str) -> Result<Flag, String> {
                 ShouldContinue::Continues
             },
         ))),
+        "-D" => Ok(Flag::TakesNextArg(Rc::new(
+            |job: &mut Job, arg: String| -> ShouldContinue {
+                // A bare name is a flag that is switched on.
+                let (name, value) = arg.split_once('=').unwrap_or((&arg, "true"));
+                job.defines
+                    .push((name.trim().to_string(), value.trim().to_string()));
+                ShouldContinue::Continues
+            },
+        ))),
         "--help" | "-h" => Ok(Flag::OnlySelf(Rc::new(
             |_job: &mut Job| -> ShouldContinue {
                 println!("[?] LiA Compiler {} Help", env!("CARGO_PKG_VERSION"));
@@ -59,6 +72,7 @@ fn parse_flag(flag: &str) -> Result<Flag, String> {
                 println!("  -w / --watch - Watch file for changes and automatically recompile.");
                 println!("  --pdflatex - Run pdflatex on the output file after compilation.");
                 println!("  -c / --chain [command] - Chain a command to run after compilation.");
+                println!("  -D [name]=[value] - Defines a constant, e.g. -D SOLUTIONS=true.");
                 println!("  --help - Prints this help message.");
                 println!("  --version - Prints the version of the LiA.");
                 ShouldContinue::Aborts
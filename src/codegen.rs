@@ -13,6 +13,10 @@ impl Node for Text {
 }
 
 impl Node for TexCommand {
+    fn as_command(&self) -> Option<&TexCommand> {
+        Some(self)
+    }
+
     fn codegen(&self) -> String {
         format!(
             "\\{}{}",
@@ -42,10 +46,12 @@ impl Node for TexCommand {
             "subsection*" => format!("<h2>{}</h2>\n", inner,),
             "subsubsection*" => format!("<h3>{}</h3>\n", inner,),
             "includegraphics" => format!("<img src=\"img/{}.png\">\n", self.args[1].html_codegen()),
-            "hline" => format!("<hr>\n"),
+            "hline" | "rule" => format!("<hr>\n"),
+            "noindent" => String::new(),
+            // Items of lists are written by the list, see `items_html`.
             "item" => "</li><li>\n".to_string(),
             "\\" | "linebreak" => "<br>".to_string(),
-            "label" => String::new(),
+            "label" => format!("<span id=\"{}\"></span>", inner),
             "Ctrl" => "<span class=\"keystroke\">Ctrl</span>".to_string(),
             "Alt" => "<span class=\"keystroke\">Alt</span>".to_string(),
             "Shift" => "<span class=\"keystroke\">Shift</span>".to_string(),
@@ -54,7 +60,49 @@ impl Node for TexCommand {
     }
 }
 
+impl Node for Footnote {
+    fn codegen(&self) -> String {
+        format!("\\footnote{{{}}}", codegen_nodelist(&self.content.borrow()))
+    }
+
+    fn codegen_html(&self) -> String {
+        format!(
+            "<sup id=\"fnref{0}\"><a href=\"#fn{0}\">{0}</a></sup>",
+            self.number
+        )
+    }
+}
+
+impl Node for FootnoteMark {
+    fn codegen(&self) -> String {
+        format!("\\footnotemark[{}]", self.number)
+    }
+
+    fn codegen_html(&self) -> String {
+        format!("<sup><a href=\"#fn{0}\">{0}</a></sup>", self.number)
+    }
+}
+
+impl Node for LabelReference {
+    fn codegen(&self) -> String {
+        let command = match self.kind.get() {
+            LabelKind::Equation => "eqref",
+            LabelKind::Section => "ref",
+            LabelKind::Other => "cref",
+        };
+        format!("\\{}{{{}}}", command, self.label)
+    }
+
+    fn codegen_html(&self) -> String {
+        format!("<a href=\"#{}\">{}</a>", self.label, self.label)
+    }
+}
+
 impl Node for TexEnvironment {
+    fn as_environment(&self) -> Option<&TexEnvironment> {
+        Some(self)
+    }
+
     fn codegen(&self) -> String {
         let mut children = self
             .children
@@ -98,9 +146,11 @@ impl Node for TexEnvironment {
 
         match self.name.as_str() {
             "verbaitum" => format!("<code\n{}</code>\n", children),
-            "itemize" => format!("<ul>\n{}</ul>\n", children),
-            "enumerate" => format!("<ol>\n{}</ol>\n", children),
+            "itemize" => format!("<ul>\n{}</ul>\n", items_html(&self.children, "li")),
+            "enumerate" => format!("<ol>\n{}</ol>\n", items_html(&self.children, "li")),
             "center" => format!("<center>\n{}</center>\n", children),
+            "quote" => format!("<blockquote>\n{}</blockquote>\n", children),
+            "description" => format!("<dl>\n{}</dl>\n", items_html(&self.children, "dd")),
             // HACK
             "tabular" => format!(
                 "<table><tr><td>\n{}</td></tr></table>\n",
@@ -109,11 +159,27 @@ impl Node for TexEnvironment {
                     .replace("<br>", "</td></tr><tr><td>")
                     .replace("<hr>", "")
             ),
-            _ => format!("<div class=\"{}\">\n{}</div>\n", self.name, children),
+            _ => format!(
+                "<div class=\"{}\">\n{}{}</div>\n",
+                self.name,
+                row_label_anchors(&children),
+                children
+            ),
         }
     }
 }
 
+/// Anchors for the `\label{...}`s written inside the rows of an aligned equation, so references
+/// to them can link somewhere.
+fn row_label_anchors(children: &str) -> String {
+    children
+        .split("\\label{")
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}'))
+        .map(|(label, _)| format!("<span id=\"{}\"></span>", label))
+        .collect()
+}
+
 impl Node for Doc {
     fn codegen(&self) -> String {
         let imps = codegen_section(&self.imports);
@@ -149,7 +215,19 @@ impl Node for Doc {
     }
 
     fn codegen_html(&self) -> String {
-        html_codegen_nodelist(&self.document)
+        let mut html = html_codegen_nodelist(&self.document);
+        if !self.footnotes.is_empty() {
+            html.push_str("<section class=\"footnotes\">\n<ol>\n");
+            for footnote in &self.footnotes {
+                html.push_str(&format!(
+                    "<li id=\"fn{0}\">{1} <a href=\"#fnref{0}\">&#8617;</a></li>\n",
+                    footnote.number,
+                    html_codegen_nodelist(&footnote.content.borrow())
+                ));
+            }
+            html.push_str("</ol>\n</section>\n");
+        }
+        html
     }
 }
 
@@ -168,6 +246,38 @@ fn codegen_nodelist(list: &NodeList) -> String {
         .collect::<String>()
 }
 
+/// Each `\item` opens an `<li>`, or a `<dt>` and `<dd>` in a description list, which the next
+/// item or the end of the list closes. The label in `\item[...]` is the term or printed first.
+fn items_html(children: &NodeList, tag: &str) -> String {
+    let mut html = String::new();
+    let mut open = false;
+    for child in children {
+        match child.as_command() {
+            Some(item) if item.command.trim() == "item" => {
+                if open {
+                    html.push_str(&format!("</{}>", tag));
+                }
+                let label = item
+                    .args
+                    .iter()
+                    .map(|arg| arg.html_codegen())
+                    .collect::<String>();
+                html.push_str(&match tag {
+                    "dd" => format!("<dt>{}</dt><dd>\n", label),
+                    _ if label.is_empty() => format!("<{}>\n", tag),
+                    _ => format!("<{}>{}", tag, label),
+                });
+                open = true;
+            }
+            _ => html.push_str(&child.codegen_html()),
+        }
+    }
+    if open {
+        html.push_str(&format!("</{}>\n", tag));
+    }
+    html
+}
+
 fn html_codegen_nodelist(list: &NodeList) -> String {
     list.iter()
         .map(|node| -> String { node.codegen_html().clone() })
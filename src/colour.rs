@@ -0,0 +1,197 @@
+use crate::builtins::argument_error;
+use crate::typed_value::TypedValue;
+
+/// Colours predefined by `xcolor` without any options.
+static XCOLOR_NAMES: [(&str, [f64; 3]); 19] = [
+    ("red", [1.0, 0.0, 0.0]),
+    ("green", [0.0, 1.0, 0.0]),
+    ("blue", [0.0, 0.0, 1.0]),
+    ("cyan", [0.0, 1.0, 1.0]),
+    ("magenta", [1.0, 0.0, 1.0]),
+    ("yellow", [1.0, 1.0, 0.0]),
+    ("black", [0.0, 0.0, 0.0]),
+    ("white", [1.0, 1.0, 1.0]),
+    ("darkgray", [0.25, 0.25, 0.25]),
+    ("gray", [0.5, 0.5, 0.5]),
+    ("lightgray", [0.75, 0.75, 0.75]),
+    ("brown", [0.75, 0.5, 0.25]),
+    ("lime", [0.75, 1.0, 0.0]),
+    ("olive", [0.5, 0.5, 0.0]),
+    ("orange", [1.0, 0.5, 0.0]),
+    ("pink", [1.0, 0.75, 0.75]),
+    ("purple", [0.75, 0.0, 0.25]),
+    ("teal", [0.0, 0.5, 0.5]),
+    ("violet", [0.5, 0.0, 0.5]),
+];
+
+/// An RGB colour with channels from 0 to 1.
+#[derive(Debug, Clone)]
+pub struct Colour {
+    /// The `xcolor` name or expression, e.g. `brand` or `red!30!blue`.
+    pub name: Option<String>,
+    pub rgb: [f64; 3],
+}
+
+impl Colour {
+    /// A predefined `xcolor` colour or one declared with `@name = #RRGGBB`.
+    pub fn named(name: &str, declared: &[Colour]) -> Option<Colour> {
+        if let Some(colour) = declared
+            .iter()
+            .rev()
+            .find(|c| c.name.as_deref() == Some(name))
+        {
+            return Some(colour.clone());
+        }
+        XCOLOR_NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(n, rgb)| Colour {
+                name: Some(n.to_string()),
+                rgb: *rgb,
+            })
+    }
+
+    /// Parses `#RRGGBB` or `#RGB`.
+    pub fn from_hex(s: &str) -> Option<Colour> {
+        let hex = s.strip_prefix('#')?;
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |digits: &str| u8::from_str_radix(digits, 16).unwrap() as f64 / 255.0;
+        let rgb = match hex.len() {
+            6 => [
+                channel(&hex[0..2]),
+                channel(&hex[2..4]),
+                channel(&hex[4..6]),
+            ],
+            3 => [0, 1, 2].map(|i| channel(&hex[i..i + 1].repeat(2))),
+            _ => return None,
+        };
+        Some(Colour { name: None, rgb })
+    }
+
+    /// Parses a colour declaration, `#RRGGBB`, `rgb(r, g, b)` or `hsl(h, s, l)`.
+    pub fn parse_literal(s: &str) -> Option<Colour> {
+        let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if s.starts_with('#') {
+            return Colour::from_hex(&s);
+        }
+        let (function, args) = s.strip_suffix(')')?.split_once('(')?;
+        let args = args
+            .split(',')
+            .map(|a| a.parse::<f64>().ok())
+            .collect::<Option<Vec<f64>>>()?;
+        match (function, args.as_slice()) {
+            ("rgb", [r, g, b]) => Some(rgb(*r, *g, *b)),
+            ("hsl", [h, s, l]) => Some(hsl(*h, *s, *l)),
+            _ => None,
+        }
+    }
+
+    /// Six hex digits for `\definecolor{name}{HTML}{...}`.
+    pub fn html(&self) -> String {
+        self.channels()
+            .iter()
+            .map(|c| format!("{:02X}", c))
+            .collect()
+    }
+
+    fn channels(&self) -> [u8; 3] {
+        self.rgb.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    /// The name if there is one, otherwise an `xcolor` extended expression that works anywhere
+    /// a colour name does, e.g. `\textcolor{rgb,255:red,31;green,119;blue,180}{text}`.
+    pub fn stringify(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => {
+                let [r, g, b] = self.channels();
+                format!("rgb,255:red,{};green,{};blue,{}", r, g, b)
+            }
+        }
+    }
+}
+
+/// `rgb(31, 119, 180)` with channels from 0 to 255.
+pub fn rgb(r: f64, g: f64, b: f64) -> Colour {
+    Colour {
+        name: None,
+        rgb: [r, g, b].map(|c| c.clamp(0.0, 255.0) / 255.0),
+    }
+}
+
+/// `hsl(210, 0.7, 0.4)` with the hue in degrees.
+pub fn hsl(h: f64, s: f64, l: f64) -> Colour {
+    let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = l - chroma / 2.0;
+    Colour {
+        name: None,
+        rgb: [r + m, g + m, b + m],
+    }
+}
+
+/// Mixes `weight` of `a` with the rest of `b`. Named colours keep an `xcolor` expression,
+/// `mix(red, blue, 0.3)` is `red!30!blue`.
+pub fn mix(a: &Colour, b: &Colour, weight: f64) -> Colour {
+    let mut rgb = [0.0; 3];
+    for (i, channel) in rgb.iter_mut().enumerate() {
+        *channel = weight * a.rgb[i] + (1.0 - weight) * b.rgb[i];
+    }
+    // xcolor reads `a!x!b!y!c` from the left, so only the right colour has to be a plain name.
+    let name = match (&a.name, &b.name) {
+        (Some(a), Some(b)) if !b.contains('!') => {
+            let percent = format!("{:.2}", weight * 100.0);
+            let percent = percent.trim_end_matches('0').trim_end_matches('.');
+            Some(format!("{}!{}!{}", a, percent, b))
+        }
+        _ => None,
+    };
+    Colour { name, rgb }
+}
+
+/// Evaluates the colour functions of @() expressions, `rgb`, `hsl`, `mix`, `lighten` and `darken`.
+pub fn call(name: &str, args: &[TypedValue]) -> Result<TypedValue, String> {
+    let number = |i: usize| match &args[i] {
+        TypedValue::Number(n) => Ok(*n),
+        value => Err(argument_error(name, i, "Number", value)),
+    };
+    let colour = |i: usize| match &args[i] {
+        TypedValue::Colour(c) => Ok(c.clone()),
+        value => Err(argument_error(name, i, "Colour", value)),
+    };
+    let weight = |i: usize| {
+        let weight = number(i)?;
+        if !(0.0..=1.0).contains(&weight) {
+            return Err(format!(
+                "Argument {} of {}() in @() expression must be between 0 and 1, found {}.",
+                i + 1,
+                name,
+                weight
+            ));
+        }
+        Ok(weight)
+    };
+    let white = Colour::named("white", &[]).unwrap();
+    let black = Colour::named("black", &[]).unwrap();
+    let result = match name {
+        "rgb" => rgb(number(0)?, number(1)?, number(2)?),
+        "hsl" => hsl(number(0)?, number(1)?, number(2)?),
+        "mix" => mix(&colour(0)?, &colour(1)?, weight(2)?),
+        "lighten" => mix(&colour(0)?, &white, 1.0 - weight(1)?),
+        "darken" => mix(&colour(0)?, &black, 1.0 - weight(1)?),
+        _ => return Err(format!("Unknown colour function \"{}\".", name)),
+    };
+    Ok(TypedValue::Colour(result))
+}
@@ -15,16 +15,19 @@ pub struct Job {
     pub debug_printing: bool,
     pub pdflatex: bool,
     pub html: bool,
+    /// Constants set with `-D NAME=value`.
+    pub defines: Vec<(String, String)>,
 }
 
-pub fn compile(job: Job) -> Result<(), String> {
+/// Returns the files the document depends on, besides the input.
+pub fn compile(job: Job) -> Result<Vec<String>, String> {
     let lia_file = match load_utf8_file(&job.input_path) {
         Ok(contents) => contents,
         Err(e) => {
             return Err(format!("{e}. Aborted."));
         }
     };
-    let output = run_compiler(lia_file, job.clone())?;
+    let (output, dependencies) = generate(lia_file, job.clone())?;
     if job.debug_printing {
         println!("{output}");
     }
@@ -57,7 +60,7 @@ pub fn compile(job: Job) -> Result<(), String> {
     }
 
     match file_res {
-        Ok(_) => Ok(()),
+        Ok(_) => Ok(dependencies),
         Err(e) => Err(format!("{e}. Aborted.")),
     }
 }
@@ -72,6 +75,10 @@ fn wait_for_child(child: &mut Result<std::process::Child, std::io::Error>) -> bo
 }
 
 pub fn run_compiler(lia_file: String, job: Job) -> Result<String, String> {
+    Ok(generate(lia_file, job)?.0)
+}
+
+fn generate(lia_file: String, job: Job) -> Result<(String, Vec<String>), String> {
     let tokens = tokenize::to_tokens(lia_file);
     let html = job.html;
     let doc = parse::parse(&tokens, job)?;
@@ -80,5 +87,5 @@ pub fn run_compiler(lia_file: String, job: Job) -> Result<String, String> {
     } else {
         doc.codegen()
     };
-    Ok(output)
+    Ok((output, doc.dependencies))
 }
@@ -0,0 +1,269 @@
+use std::path::Path;
+use std::rc::Rc;
+
+use serde_json::Value;
+
+use crate::at_expression::string_to_typed_value;
+use crate::document::{Arg, ArgType, Node, TexEnvironment, Text};
+use crate::number_format::{format_value, NumberFormat};
+use crate::parse::CompilerGlobals;
+use crate::typed_value::TypedValue;
+use crate::utils::load_utf8_file;
+
+/// Reads a `.csv` or `.json` file. A CSV file is a list of its rows, each a record of its columns.
+pub fn load(path: &Path) -> Result<TypedValue, String> {
+    let contents = load_utf8_file(&path.to_string_lossy().to_string())
+        .map_err(|e| format!("Could not read \"{}\": {}.", path.display(), e))?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("csv") => load_csv(&contents)
+            .map_err(|e| format!("Could not read \"{}\": {}.", path.display(), e)),
+        Some("json") => serde_json::from_str::<Value>(&contents)
+            .map(from_json)
+            .map_err(|e| format!("Could not read \"{}\": {}.", path.display(), e)),
+        _ => Err(format!(
+            "Can't load \"{}\", only .csv and .json files are supported.",
+            path.display()
+        )),
+    }
+}
+
+fn load_csv(contents: &str) -> Result<TypedValue, csv::Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(contents.as_bytes());
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let fields = headers
+            .iter()
+            .cloned()
+            .zip(record?.iter().map(cell_value))
+            .collect();
+        rows.push(TypedValue::Record(fields));
+    }
+    Ok(TypedValue::List(rows))
+}
+
+/// Numbers and `true`/`false` are read as such, anything else is text.
+fn cell_value(cell: &str) -> TypedValue {
+    match string_to_typed_value(cell.to_string()) {
+        Ok(value @ (TypedValue::Number(_) | TypedValue::Bool(_))) => value,
+        _ => TypedValue::String(cell.to_string()),
+    }
+}
+
+fn from_json(value: Value) -> TypedValue {
+    match value {
+        Value::Null => TypedValue::String(String::new()),
+        Value::Bool(b) => TypedValue::Bool(b),
+        Value::Number(n) => TypedValue::Number(n.as_f64().unwrap_or(f64::NAN)),
+        Value::String(s) => TypedValue::String(s),
+        Value::Array(items) => TypedValue::List(items.into_iter().map(from_json).collect()),
+        Value::Object(fields) => {
+            TypedValue::Record(fields.into_iter().map(|(k, v)| (k, from_json(v))).collect())
+        }
+    }
+}
+
+/// The value of a constant or part of it, e.g. `data[2].voltage`. `None` if `data` is not a constant.
+pub fn lookup(path: &str, globals: &CompilerGlobals) -> Option<Result<TypedValue, String>> {
+    let root = &path[..path.find(['.', '[']).unwrap_or(path.len())];
+    let value = globals.constant(root)?;
+    Some(access(value, &path[root.len()..], path, globals))
+}
+
+fn access(
+    value: &TypedValue,
+    mut rest: &str,
+    path: &str,
+    globals: &CompilerGlobals,
+) -> Result<TypedValue, String> {
+    let mut value = value.clone();
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix('.') {
+            let end = r.find(['.', '[']).unwrap_or(r.len());
+            value = field(&value, &r[..end], path)?;
+            rest = &r[end..];
+        } else if let Some(r) = rest.strip_prefix('[') {
+            let Some(end) = r.find(']') else {
+                return Err(format!("Missing \"]\" in \"{}\".", path));
+            };
+            value = item(&value, index(r[..end].trim(), path, globals)?, path)?;
+            rest = &r[end + 1..];
+        } else {
+            return Err(format!("Expected \".\" or \"[\" in \"{}\".", path));
+        }
+    }
+    Ok(value)
+}
+
+/// A whole number or a constant holding one, e.g. `2` or `i` in a loop.
+fn index(index: &str, path: &str, globals: &CompilerGlobals) -> Result<usize, String> {
+    let value = match index.parse::<f64>() {
+        Ok(n) => Some(TypedValue::Number(n)),
+        Err(_) => globals.constant(index).cloned(),
+    };
+    match value {
+        Some(TypedValue::Number(n)) if n >= 0.0 && n.fract() == 0.0 => Ok(n as usize),
+        _ => Err(format!(
+            "Expected a whole number as index in \"{}\", found \"{}\".",
+            path, index
+        )),
+    }
+}
+
+fn item(value: &TypedValue, i: usize, path: &str) -> Result<TypedValue, String> {
+    let (item, len) = match value {
+        TypedValue::List(items) => (items.get(i).cloned(), items.len()),
+        TypedValue::Vector(v) => (v.get(i).cloned().map(TypedValue::Number), v.len()),
+        TypedValue::Matrix(m) => (m.get(i).cloned().map(TypedValue::Vector), m.len()),
+        value => {
+            return Err(format!(
+                "Can't index {} in \"{}\".",
+                value.type_name(),
+                path
+            ))
+        }
+    };
+    item.ok_or_else(|| {
+        format!(
+            "Index {} is out of range in \"{}\", which has {} items. The first item is [0].",
+            i, path, len
+        )
+    })
+}
+
+fn field(value: &TypedValue, name: &str, path: &str) -> Result<TypedValue, String> {
+    let TypedValue::Record(fields) = value else {
+        return Err(format!(
+            "{} has no field \"{}\" in \"{}\".",
+            value.type_name(),
+            name,
+            path
+        ));
+    };
+    match fields.iter().find(|(k, _)| k == name) {
+        Some((_, v)) => Ok(v.clone()),
+        None => Err(format!(
+            "No field \"{}\" in \"{}\", available fields are {}.",
+            name,
+            path,
+            field_names(fields).join(", ")
+        )),
+    }
+}
+
+fn field_names(fields: &[(String, TypedValue)]) -> Vec<String> {
+    fields.iter().map(|(k, _)| k.clone()).collect()
+}
+
+/// Renders a list of records as a `tabular`, with the columns in `columns` titled `headers`.
+pub fn table(
+    data: &TypedValue,
+    columns: Option<Vec<String>>,
+    headers: Option<Vec<String>>,
+    format: Option<&NumberFormat>,
+) -> Result<Rc<dyn Node>, String> {
+    let TypedValue::List(rows) = data else {
+        return Err(format!(
+            "Expected the rows of a data file, found {}.",
+            data.type_name()
+        ));
+    };
+    let mut records = Vec::new();
+    for row in rows {
+        match row {
+            TypedValue::Record(fields) => records.push(fields),
+            row => {
+                return Err(format!(
+                    "Expected every row to have named columns, found {}.",
+                    row.type_name()
+                ))
+            }
+        }
+    }
+    let available = records.first().map(|r| field_names(r)).unwrap_or_default();
+    let columns = columns.unwrap_or_else(|| available.clone());
+    if let Some(missing) = columns.iter().find(|c| !available.contains(c)) {
+        return Err(format!(
+            "No column \"{}\" in the data, available columns are {}.",
+            missing,
+            available.join(", ")
+        ));
+    }
+    let headers = match headers {
+        Some(headers) if headers.len() != columns.len() => {
+            return Err(format!(
+                "Expected {} headers, one for each column, found {}.",
+                columns.len(),
+                headers.len()
+            ))
+        }
+        Some(headers) => headers,
+        None => columns.iter().map(|c| escape(c)).collect(),
+    };
+
+    let cells: Vec<Vec<Option<&TypedValue>>> = records
+        .iter()
+        .map(|fields| {
+            columns
+                .iter()
+                .map(|c| fields.iter().find(|(k, _)| k == c).map(|(_, v)| v))
+                .collect()
+        })
+        .collect();
+    // Numbers are right aligned so their digits line up.
+    let alignment: String = (0..columns.len())
+        .map(|i| {
+            let numeric = cells
+                .iter()
+                .all(|row| matches!(row[i], None | Some(TypedValue::Number(_))));
+            if numeric {
+                'r'
+            } else {
+                'l'
+            }
+        })
+        .collect();
+    let mut lines = vec![
+        format!("{} \\\\", headers.join(" & ")),
+        "\\hline".to_string(),
+    ];
+    for row in &cells {
+        let row: Vec<String> = row
+            .iter()
+            .map(|cell| match cell {
+                Some(TypedValue::String(s)) => Ok(escape(s)),
+                Some(value) => format_value(value, format),
+                None => Ok(String::new()),
+            })
+            .collect::<Result<_, _>>()?;
+        lines.push(format!("{} \\\\", row.join(" & ")));
+    }
+    Ok(Rc::new(TexEnvironment {
+        name: "tabular".to_string(),
+        args: vec![Arg {
+            arg_type: ArgType::Curly,
+            arg: vec![Rc::new(Text { text: alignment })],
+        }],
+        children: vec![Rc::new(Text {
+            text: lines.join("\n"),
+        })],
+    }))
+}
+
+/// Text read from a data file is printed as is, not read as TeX.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
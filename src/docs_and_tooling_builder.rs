@@ -4,6 +4,7 @@ use std::error::Error;
 
 use lia::{
     compiler::{run_compiler, Job},
+    grammar::symbols::{generate_docs, generate_regex},
     utils::{load_utf8_file, write_utf8_file},
 };
 
@@ -11,6 +12,11 @@ static COMP_IN_OPEN: &str = "[COMPILATION_INPUT_START]: <> (Do not remove this l
 static COMP_IN_CLOSE: &str = "[COMPILATION_INPUT_END]: <> (Do not remove this line.)";
 static COMP_OUT_OPEN: &str = "[COMPILATION_RESULT_START]: <> (Do not remove this line.)";
 static COMP_OUT_CLOSE: &str = "[COMPILATION_RESULT_END]: <> (Do not remove this line.)";
+static SYMBOLS_OPEN: &str = "[SYMBOLS_START]: <> (Do not remove this line.)";
+static SYMBOLS_CLOSE: &str = "[SYMBOLS_END]: <> (Do not remove this line.)";
+static SYNTAX_PATH: &str = "tooling/vscode/lia-helper/syntaxes/lia.tmLanguage.json";
+// Prefix of the generated equation keyword regex in the syntax file.
+static SYNTAX_REGEX_PREFIX: &str = "\"match\": \"(<->";
 
 fn main() {
     match build_docs() {
@@ -22,16 +28,49 @@ fn main() {
 fn build_docs() -> Result<(), Box<dyn Error>> {
     proc_file("docs.md", true)?;
     proc_file("README.md", false)?;
+    proc_syntax_file()?;
     Ok(())
 }
 
 fn proc_file(path: &str, strips_doc_env: bool) -> Result<(), Box<dyn Error>> {
     let f = load_utf8_file(&path.to_string())?;
     let f = do_compilations(f, strips_doc_env);
+    let f = insert_symbol_table(f);
     write_utf8_file(path.to_string(), f)?;
     Ok(())
 }
 
+fn insert_symbol_table(s: String) -> String {
+    let (before, rest) = match s.split_once(SYMBOLS_OPEN) {
+        Some(split) => split,
+        None => return s,
+    };
+    let after = match rest.split_once(SYMBOLS_CLOSE) {
+        Some((_, after)) => after,
+        None => return s,
+    };
+    format!(
+        "{before}{SYMBOLS_OPEN}\n{}{SYMBOLS_CLOSE}{after}",
+        generate_docs()
+    )
+}
+
+fn proc_syntax_file() -> Result<(), Box<dyn Error>> {
+    let f = load_utf8_file(&SYNTAX_PATH.to_string())?;
+    let regex = generate_regex().replace('\\', "\\\\");
+    let trailing_newline = if f.ends_with('\n') { "\n" } else { "" };
+    let f = f
+        .lines()
+        .map(|line| match line.find(SYNTAX_REGEX_PREFIX) {
+            Some(i) => format!("{}\"match\": \"{}\"", &line[..i], regex),
+            None => line.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    write_utf8_file(SYNTAX_PATH.to_string(), f + trailing_newline)?;
+    Ok(())
+}
+
 // Imperative mess...
 fn do_compilations(s: String, strips_doc_env: bool) -> String {
     let spl = s.split(COMP_IN_OPEN).collect::<Vec<&str>>();
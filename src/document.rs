@@ -1,3 +1,4 @@
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 pub type NodeList = Vec<Rc<dyn Node>>;
@@ -7,6 +8,9 @@ pub struct Doc {
     pub imports: NodeList,
     pub declarations: NodeList,
     pub document: NodeList,
+    pub footnotes: Vec<Rc<Footnote>>,
+    /// Files read while compiling, watched along with the input.
+    pub dependencies: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -31,9 +35,38 @@ pub struct TexEnvironment {
     pub children: NodeList,
 }
 
+pub struct Footnote {
+    pub number: usize,
+    pub content: Rc<RefCell<NodeList>>,
+}
+
+/// A footnote referenced again, `\footnotemark[n]`.
+pub struct FootnoteMark {
+    pub number: usize,
+}
+
+pub struct LabelReference {
+    pub label: String,
+    pub kind: Rc<Cell<LabelKind>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LabelKind {
+    Equation,
+    Section,
+    #[default]
+    Other,
+}
+
 pub trait Node {
     fn codegen(&self) -> String;
     fn codegen_html(&self) -> String;
+    fn as_environment(&self) -> Option<&TexEnvironment> {
+        None
+    }
+    fn as_command(&self) -> Option<&TexCommand> {
+        None
+    }
 }
 
 pub enum ArgType {
@@ -33,7 +33,7 @@ pub fn get_status_list(version_: &str) -> Result<FeatureStatusList, String> {
     Ok(status_list)
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub enum ImplementationStatus {
     #[default]
     NotImplemented,
@@ -49,7 +49,7 @@ impl ImplementationStatus {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FeatureStatusList {
     pub enumerated_lists: ImplementationStatus,
     pub equation_statement_internal_syntax: ImplementationStatus,
@@ -0,0 +1,443 @@
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::at_expression::AtExpToken;
+use crate::token::Token;
+use crate::typed_value::TypedValue;
+
+use super::token_from_list;
+
+pub static BIG_OPERATORS: [&str; 8] = ["sum", "prod", "int", "lim", "sqrt", "root", "abs", "norm"];
+
+/// Iterations a `sum()` or `prod()` may run for in an @() expression.
+const MAX_ITERATIONS: f64 = 1_000_000.0;
+/// Intervals used to numerically integrate `int()`, must be even.
+const INTEGRATION_INTERVALS: usize = 1024;
+/// Distances from the target `lim()` is evaluated at, relative to the target.
+const LIMIT_STEPS: [f64; 4] = [1e-2, 1e-3, 1e-4, 1e-5];
+/// Relative difference allowed between the last steps, and between both sides, of a `lim()`.
+const LIMIT_TOLERANCE: f64 = 1e-4;
+/// Significant digits an evaluated `lim()` is rounded to.
+const LIMIT_DIGITS: usize = 6;
+
+enum Arg {
+    Node(DefAstNode),
+    /// `i = 0` or `x -> 0` with `i` or `x` bound inside the operator.
+    Binding(usize, String, DefAstNode),
+    /// The variable of integration.
+    Variable(usize, String),
+}
+
+impl Arg {
+    fn codegen(&self) -> String {
+        match self {
+            Arg::Node(node) => node.codegen(),
+            Arg::Binding(_, name, node) => format!("{} = {}", name, node.codegen()),
+            Arg::Variable(_, name) => name.clone(),
+        }
+    }
+
+    fn node(&self) -> Option<&DefAstNode> {
+        match self {
+            Arg::Node(node) | Arg::Binding(_, _, node) => Some(node),
+            Arg::Variable(_, _) => None,
+        }
+    }
+
+    fn evaluate_number(
+        &self,
+        imported_values: &Vec<TypedValue>,
+        operator: &str,
+    ) -> Result<f64, String> {
+        match self.node() {
+            Some(node) => match node.evaluate(imported_values)? {
+                TypedValue::Number(n) => Ok(n),
+                value => Err(format!(
+                    "Expected a Number as argument of {}() in @() expression, found {}.",
+                    operator,
+                    value.type_name()
+                )),
+            },
+            None => Err(format!(
+                "Can't evaluate the variable of {}() in @() expression.",
+                operator
+            )),
+        }
+    }
+}
+
+pub struct BigOperator {
+    name: String,
+    args: Vec<Arg>,
+}
+
+impl AstNode for BigOperator {
+    fn evaluate(&self, imported_values: &Vec<TypedValue>) -> Result<TypedValue, String> {
+        let number = |i: usize| self.args[i].evaluate_number(imported_values, &self.name);
+        let result = match self.name.as_str() {
+            "sum" | "prod" => {
+                let index = self.bound_index(0)?;
+                let (from, to) = (number(0)?, number(1)?);
+                if from.fract() != 0.0 || to.fract() != 0.0 {
+                    return Err(format!(
+                        "Bounds of {}() must be whole numbers in @() expression, found {} and {}.",
+                        self.name, from, to
+                    ));
+                }
+                if to - from >= MAX_ITERATIONS {
+                    return Err(format!(
+                        "{}() in @() expression would run for more than {} iterations.",
+                        self.name, MAX_ITERATIONS
+                    ));
+                }
+                let mut result = if self.name == "sum" { 0.0 } else { 1.0 };
+                let mut i = from;
+                while i <= to {
+                    let value = self.evaluate_body(2, imported_values, index, i)?;
+                    if self.name == "sum" {
+                        result += value;
+                    } else {
+                        result *= value;
+                    }
+                    i += 1.0;
+                }
+                result
+            }
+            "int" => {
+                if self.args.len() != 4 {
+                    return Err(
+                        "Can't evaluate an integral without bounds in @() expression.".to_string(),
+                    );
+                }
+                let index = self.bound_index(3)?;
+                let (from, to) = (number(0)?, number(1)?);
+                // Simpson's rule
+                let h = (to - from) / INTEGRATION_INTERVALS as f64;
+                let mut result = 0.0;
+                for k in 0..=INTEGRATION_INTERVALS {
+                    let weight = if k == 0 || k == INTEGRATION_INTERVALS {
+                        1.0
+                    } else if k % 2 == 1 {
+                        4.0
+                    } else {
+                        2.0
+                    };
+                    let x = from + h * k as f64;
+                    result += weight * self.evaluate_body(2, imported_values, index, x)?;
+                }
+                result * h / 3.0
+            }
+            "lim" => {
+                let index = self.bound_index(0)?;
+                let to = number(0)?;
+                if !to.is_finite() {
+                    return Err(format!(
+                        "Can only evaluate limits at a finite value in @() expression, found {}.",
+                        to
+                    ));
+                }
+                let below = self.one_sided_limit(imported_values, index, to, -1.0);
+                let above = self.one_sided_limit(imported_values, index, to, 1.0);
+                let limit = match (below, above) {
+                    (Some(below), Some(above))
+                        if (below - above).abs() <= LIMIT_TOLERANCE * below.abs().max(1.0) =>
+                    {
+                        (below + above) / 2.0
+                    }
+                    _ => {
+                        return Err(format!(
+                            "Could not find the limit as the variable approaches {} in @() expression.",
+                            to
+                        ));
+                    }
+                };
+                // Only the first digits of a sampled limit can be trusted.
+                format!("{:.*e}", LIMIT_DIGITS - 1, limit)
+                    .parse::<f64>()
+                    .unwrap()
+            }
+            "sqrt" => {
+                let x = number(0)?;
+                if x < 0.0 {
+                    return Err(format!(
+                        "Can't take the square root of negative number {} in @() expression.",
+                        x
+                    ));
+                }
+                x.sqrt()
+            }
+            "root" => {
+                let (n, x) = (number(0)?, number(1)?);
+                if x < 0.0 && n.fract() == 0.0 && n % 2.0 != 0.0 {
+                    -(-x).powf(1.0 / n)
+                } else if x < 0.0 {
+                    return Err(format!(
+                        "Can't take an even root of negative number {} in @() expression.",
+                        x
+                    ));
+                } else {
+                    x.powf(1.0 / n)
+                }
+            }
+            _ => number(0)?.abs(),
+        };
+        Ok(TypedValue::Number(result))
+    }
+
+    fn codegen(&self) -> String {
+        let arg = |i: usize| self.args[i].codegen();
+        let body = |i: usize| bracketed(arg(i));
+        match self.name.as_str() {
+            "sum" => format!("\\sum_{{{}}}^{{{}}} {}", arg(0), arg(1), body(2)),
+            "prod" => format!("\\prod_{{{}}}^{{{}}} {}", arg(0), arg(1), body(2)),
+            "int" if self.args.len() == 2 => format!("\\int {} \\,d{}", body(0), arg(1)),
+            "int" => format!(
+                "\\int_{{{}}}^{{{}}} {} \\,d{}",
+                arg(0),
+                arg(1),
+                body(2),
+                arg(3)
+            ),
+            "lim" => {
+                let approach = match &self.args[0] {
+                    Arg::Binding(_, name, node) => format!("{} \\to {}", name, node.codegen()),
+                    arg => arg.codegen().replace("\\rightarrow", "\\to"),
+                };
+                format!("\\lim_{{{}}} {}", approach, body(1))
+            }
+            "sqrt" => format!("\\sqrt{{{}}}", arg(0)),
+            "root" => format!("\\sqrt[{}]{{{}}}", arg(0), arg(1)),
+            "abs" => format!("\\left| {} \\right|", arg(0)),
+            _ => format!("\\left\\| {} \\right\\|", arg(0)),
+        }
+    }
+}
+
+impl BigOperator {
+    fn bound_index(&self, arg: usize) -> Result<usize, String> {
+        match &self.args[arg] {
+            Arg::Binding(index, _, _) | Arg::Variable(index, _) => Ok(*index),
+            Arg::Node(_) => Err(format!(
+                "Can't evaluate {}() without a variable to iterate over in @() expression.",
+                self.name
+            )),
+        }
+    }
+
+    /// Approaches `to` from the side of `direction`. Gives up unless the body settles over the
+    /// last steps, so slowly diverging bodies like `log(x)` at 0 don't give a finite answer.
+    fn one_sided_limit(
+        &self,
+        imported_values: &[TypedValue],
+        index: usize,
+        to: f64,
+        direction: f64,
+    ) -> Option<f64> {
+        let scale = to.abs().max(1.0);
+        let mut values = Vec::new();
+        for h in LIMIT_STEPS {
+            let value = self
+                .evaluate_body(1, imported_values, index, to + direction * h * scale)
+                .ok()?;
+            if !value.is_finite() {
+                return None;
+            }
+            values.push(value);
+        }
+        let last = *values.last()?;
+        let tolerance = LIMIT_TOLERANCE * last.abs().max(1.0);
+        let [.., a, b, c] = values[..] else {
+            return None;
+        };
+        // Each step is ten times closer, so the body should settle at least that quickly.
+        let settles = (c - b).abs() <= tolerance && (b - a).abs() <= 10.0 * tolerance;
+        settles.then_some(last)
+    }
+
+    /// Evaluates the argument `arg` with the bound variable set to `value`.
+    fn evaluate_body(
+        &self,
+        arg: usize,
+        imported_values: &[TypedValue],
+        index: usize,
+        value: f64,
+    ) -> Result<f64, String> {
+        let mut values = imported_values.to_vec();
+        if values.len() <= index {
+            values.resize(index + 1, TypedValue::Number(0.0));
+        }
+        values[index] = TypedValue::Number(value);
+        self.args[arg].evaluate_number(&values, &self.name)
+    }
+}
+
+fn arg_count(name: &str) -> &'static [usize] {
+    match name {
+        "sum" | "prod" => &[3],
+        "int" => &[2, 4],
+        "lim" | "root" => &[2],
+        _ => &[1],
+    }
+}
+
+/// Wraps sums and differences so they read as a single operand.
+fn bracketed(code: String) -> String {
+    let mut depth = 0;
+    let mut top_level_sum = false;
+    let mut escaped = false;
+    for (i, c) in code.char_indices() {
+        let rest = &code[i..];
+        // `\{` and `\}` are delimiters, which only count with a `\left` or `\right` before them.
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        if starts_with_command(rest, "\\left") || c == '{' {
+            depth += 1;
+        } else if starts_with_command(rest, "\\right") || c == '}' {
+            depth -= 1;
+        } else if depth == 0 && i > 0 && (rest.starts_with(" + ") || rest.starts_with(" - ")) {
+            top_level_sum = true;
+        }
+        escaped = c == '\\';
+    }
+    if top_level_sum {
+        format!("\\left({}\\right)", code)
+    } else {
+        code
+    }
+}
+
+/// Whether `code` starts with `command` as a whole command, so `\right` doesn't match `\rightarrow`.
+fn starts_with_command(code: &str, command: &str) -> bool {
+    code.strip_prefix(command)
+        .is_some_and(|rest| !rest.starts_with(|c: char| c.is_ascii_alphabetic()))
+}
+
+pub fn is_big_operator_name(name: &str) -> bool {
+    BIG_OPERATORS.contains(&name)
+}
+
+pub fn is_big_operator(tokens: &Vec<AtExpToken>, pos: i32) -> bool {
+    match token_from_list(tokens, pos) {
+        AtExpToken::Text(name) => {
+            is_big_operator_name(&name)
+                && token_from_list(tokens, pos + 1).is_opertor_or_keyword("(")
+        }
+        _ => false,
+    }
+}
+
+pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String> {
+    if !is_big_operator(tokens, start) {
+        return Ok(None);
+    }
+    let name = match token_from_list(tokens, start) {
+        AtExpToken::Text(name) => name,
+        _ => return Ok(None),
+    };
+    let mut args = Vec::new();
+    let mut current: Vec<AtExpToken> = Vec::new();
+    let mut pos = start + 2;
+    loop {
+        let token = token_from_list(tokens, pos);
+        if token.is_opertor_or_keyword(",") || token.is_opertor_or_keyword(")") {
+            match to_arg(&current) {
+                Some(arg) => args.push(arg),
+                None => return Ok(None),
+            }
+            current.clear();
+            if token.is_opertor_or_keyword(")") {
+                break;
+            }
+        } else if let AtExpToken::Error = token {
+            return Ok(None);
+        } else {
+            current.push(token);
+        }
+        pos += 1;
+    }
+    let expected = arg_count(&name);
+    if !expected.contains(&args.len()) {
+        return Err(format!(
+            "Expected {} arguments for {}(), found {}.",
+            expected
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<String>>()
+                .join(" or "),
+            name,
+            args.len()
+        ));
+    }
+    Ok(Some((
+        Rc::new(BigOperator { name, args }),
+        (pos - start + 1) as usize,
+    )))
+}
+
+fn to_arg(tokens: &[AtExpToken]) -> Option<Arg> {
+    match tokens {
+        [AtExpToken::AstNode(node)] => Some(Arg::Node(node.clone())),
+        [AtExpToken::BoundIdentifier(index, name)] => Some(Arg::Variable(*index, name.clone())),
+        [AtExpToken::BoundIdentifier(index, name), AtExpToken::OperatorOrKeyword(op), AtExpToken::AstNode(node)]
+            if op == "=" || op == "->" =>
+        {
+            Some(Arg::Binding(*index, name.clone(), node.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Variables bound by big operators in an @() expression, e.g. `i` in `sum(i = 0, n, i)`.
+pub struct BoundVariable {
+    pub name: String,
+    /// Position of the token declaring the variable.
+    pub declaration: usize,
+    /// Positions of the brackets of the operator.
+    pub scope: (usize, usize),
+}
+
+pub fn find_bound_variables(tokens: &[Token]) -> Vec<BoundVariable> {
+    let text = |i: usize| match tokens.get(i) {
+        Some(Token::Misc(t, _)) => t.as_str(),
+        _ => "",
+    };
+    let mut bound = Vec::new();
+    for i in 0..tokens.len() {
+        if text(i + 1) != "(" || !is_big_operator_name(text(i)) {
+            continue;
+        }
+        let mut depth = 0;
+        let mut close = tokens.len();
+        let mut last_comma = None;
+        for j in i + 1..tokens.len() {
+            match text(j) {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = j;
+                        break;
+                    }
+                }
+                "," if depth == 1 => last_comma = Some(j),
+                _ => {}
+            }
+        }
+        let declaration = match text(i) {
+            "sum" | "prod" if text(i + 3) == "=" => Some(i + 2),
+            "lim" if text(i + 3) == "->" => Some(i + 2),
+            "int" => last_comma.filter(|c| c + 2 == close).map(|c| c + 1),
+            _ => None,
+        };
+        if let Some(declaration) = declaration {
+            bound.push(BoundVariable {
+                name: text(declaration).to_string(),
+                declaration,
+                scope: (i + 1, close),
+            });
+        }
+    }
+    bound
+}
@@ -2,6 +2,8 @@ use std::rc::Rc;
 
 use crate::ast::*;
 use crate::at_expression::AtExpToken;
+use crate::linear_algebra;
+use crate::size;
 use crate::typed_value::TypedValue;
 
 use super::{check_either_side_for_opers, token_from_list};
@@ -62,7 +64,7 @@ pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String>
                 || (token_from_list(tokens, start - 1).is_opertor_or_keyword("-"))))
             || sub)
         && token_from_list(tokens, start + 2).is_ast_node()
-        && !check_either_side_for_opers(tokens, start, 3, vec!["*", "/", "%", "^"])
+        && !check_either_side_for_opers(tokens, start, 3, vec!["*", "/", "%", "^", "_"])
     {
         Ok(Some((
             Rc::new(BinaryAdditiveExpression {
@@ -80,7 +82,14 @@ pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String>
 }
 
 fn sub(lhs: TypedValue, rhs: TypedValue) -> Result<TypedValue, String> {
+    if is_linear_algebra(&lhs) || is_linear_algebra(&rhs) {
+        return linear_algebra::add(&lhs, &rhs, -1.0);
+    }
     match lhs {
+        TypedValue::Size(lhs) => match rhs {
+            TypedValue::Size(rhs) => size::add(&lhs, &rhs, -1.0),
+            _ => Err("Tried to subtract mismatched types in @() expression.".to_string()),
+        },
         TypedValue::Number(lhs) => match rhs {
             TypedValue::Number(rhs) => Ok(TypedValue::Number(lhs - rhs)),
             _ => Err("Tried to subtract mismatched types in @() expression.".to_string()),
@@ -90,7 +99,24 @@ fn sub(lhs: TypedValue, rhs: TypedValue) -> Result<TypedValue, String> {
 }
 
 fn add(lhs: &TypedValue, rhs: &TypedValue) -> Result<TypedValue, String> {
+    if is_linear_algebra(lhs) || is_linear_algebra(rhs) {
+        return linear_algebra::add(lhs, rhs, 1.0);
+    }
+    // Numbers, sizes and booleans are printed when added to a string.
+    match (lhs, rhs) {
+        (TypedValue::String(s), other) if is_printable(other) => {
+            return Ok(TypedValue::String(s.clone() + &other.stringify()))
+        }
+        (other, TypedValue::String(s)) if is_printable(other) => {
+            return Ok(TypedValue::String(other.stringify() + s))
+        }
+        _ => {}
+    }
     match lhs {
+        TypedValue::Size(lhs) => match rhs {
+            TypedValue::Size(rhs) => size::add(lhs, rhs, 1.0),
+            _ => Err("Tried to add mismatched types in @() expression.".to_string()),
+        },
         TypedValue::Number(lhs) => match rhs {
             TypedValue::Number(rhs) => Ok(TypedValue::Number(lhs + rhs)),
             _ => Err("Tried to add mismatched types in @() expression.".to_string()),
@@ -99,5 +125,17 @@ fn add(lhs: &TypedValue, rhs: &TypedValue) -> Result<TypedValue, String> {
             TypedValue::String(rhs) => Ok(TypedValue::String(lhs.clone() + rhs)),
             _ => Err("Tried to add mismatched types in @() expression.".to_string()),
         },
+        _ => Err("Tried to add a forbidden type in @() expression.".to_string()),
     }
 }
+
+fn is_printable(value: &TypedValue) -> bool {
+    matches!(
+        value,
+        TypedValue::Number(_) | TypedValue::Size(_) | TypedValue::Bool(_)
+    )
+}
+
+fn is_linear_algebra(value: &TypedValue) -> bool {
+    matches!(value, TypedValue::Vector(_) | TypedValue::Matrix(_))
+}
@@ -2,6 +2,8 @@ use std::rc::Rc;
 
 use crate::ast::*;
 use crate::at_expression::AtExpToken;
+use crate::linear_algebra;
+use crate::size;
 use crate::typed_value::TypedValue;
 
 use super::{check_either_side_for_opers, token_from_list};
@@ -54,6 +56,33 @@ impl AstNode for BinaryMultiplicativeExpression {
 
 impl BinaryMultiplicativeExpression {
     fn operate(&self, lhs: TypedValue, rhs: TypedValue) -> Result<TypedValue, String> {
+        match (&self.operation, &lhs, &rhs) {
+            (Operation::Mul, TypedValue::Vector(_) | TypedValue::Matrix(_), _)
+            | (Operation::Mul, _, TypedValue::Vector(_) | TypedValue::Matrix(_)) => {
+                return linear_algebra::multiply(&lhs, &rhs);
+            }
+            (
+                Operation::Div,
+                TypedValue::Vector(_) | TypedValue::Matrix(_),
+                TypedValue::Number(n),
+            ) => {
+                return linear_algebra::divide(&lhs, *n);
+            }
+            (Operation::Mul, TypedValue::Size(s), TypedValue::Number(n))
+            | (Operation::Mul, TypedValue::Number(n), TypedValue::Size(s)) => {
+                return size::checked(s.scale(*n));
+            }
+            (Operation::Div, TypedValue::Size(s), TypedValue::Number(n)) => {
+                if *n == 0.0 {
+                    return Err("Can't divide a size by zero in @() expression.".to_string());
+                }
+                return size::checked(s.scale(1.0 / n));
+            }
+            (Operation::Div, TypedValue::Size(a), TypedValue::Size(b)) => {
+                return size::ratio(a, b);
+            }
+            _ => {}
+        }
         match lhs {
             TypedValue::Number(lhs) => match rhs {
                 TypedValue::Number(rhs) => self.evaluate_operation(lhs, rhs),
@@ -79,7 +108,7 @@ pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String>
     if token_from_list(tokens, start).is_ast_node()
         && (mul || div || _mod)
         && token_from_list(tokens, start + 2).is_ast_node()
-        && !check_either_side_for_opers(tokens, start, 3, vec!["^"])
+        && !check_either_side_for_opers(tokens, start, 3, vec!["^", "_"])
     {
         if mul && check_either_side_for_opers(tokens, start, 3, vec!["/"]) {
             return Ok(None);
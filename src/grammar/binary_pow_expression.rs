@@ -4,7 +4,8 @@ use crate::ast::*;
 use crate::at_expression::AtExpToken;
 use crate::typed_value::TypedValue;
 
-use super::token_from_list;
+use super::subscript_expression::script_operand;
+use super::{check_either_side_for_opers, token_from_list};
 
 pub struct BinaryPowExpression {
     children: (AtExpToken, AtExpToken),
@@ -28,7 +29,7 @@ impl AstNode for BinaryPowExpression {
     fn codegen(&self) -> String {
         if let AtExpToken::AstNode(left) = &self.children.0 {
             if let AtExpToken::AstNode(right) = &self.children.1 {
-                format!("{}^{}", left.codegen(), right.codegen())
+                format!("{}^{}", left.codegen(), script_operand(right))
             } else {
                 panic!("BinaryAdditionOperator::codegen() called with non-AstNode token in right position.")
             }
@@ -58,6 +59,7 @@ pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String>
     if token_from_list(tokens, start).is_ast_node()
         && token_from_list(tokens, start + 1).is_opertor_or_keyword("^")
         && token_from_list(tokens, start + 2).is_ast_node()
+        && !check_either_side_for_opers(tokens, start, 3, vec!["_"])
     {
         Ok(Some((
             Rc::new(BinaryPowExpression {
@@ -0,0 +1,146 @@
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::at_expression::AtExpToken;
+use crate::typed_value::TypedValue;
+
+use super::token_from_list;
+
+enum Condition {
+    If(DefAstNode),
+    Otherwise,
+    /// `value, condition` without `if`.
+    Plain(DefAstNode),
+    None,
+}
+
+struct Case {
+    value: DefAstNode,
+    condition: Condition,
+}
+
+/// A piecewise definition, `cases { x^2 if x >= 0; -x otherwise }`.
+pub struct Cases {
+    cases: Vec<Case>,
+}
+
+#[allow(unused)]
+impl AstNode for Cases {
+    fn evaluate(&self, imported_values: &Vec<TypedValue>) -> Result<TypedValue, String> {
+        Err("Can't evaluate cases in @() expression.".to_string())
+    }
+
+    fn codegen(&self) -> String {
+        let rows = self
+            .cases
+            .iter()
+            .map(|case| {
+                let value = case.value.codegen();
+                match &case.condition {
+                    Condition::If(condition) => {
+                        format!("{} & \\text{{if }} {}", value, condition.codegen())
+                    }
+                    Condition::Otherwise => format!("{} & \\text{{otherwise}}", value),
+                    Condition::Plain(condition) => format!("{} & {}", value, condition.codegen()),
+                    Condition::None => value,
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" \\\\ ");
+        format!("\\begin{{cases}} {} \\end{{cases}}", rows)
+    }
+}
+
+pub fn is_cases_opener(tokens: &Vec<AtExpToken>, pos: i32) -> bool {
+    matches!(token_from_list(tokens, pos), AtExpToken::Text(t) if t == "cases")
+        && token_from_list(tokens, pos + 1).is_opertor_or_keyword("{")
+}
+
+/// Turns `;`, `if` and `otherwise` directly inside `cases {}` into keywords.
+pub fn mark_case_keywords(tokens: &mut [AtExpToken]) {
+    let mut depths: Vec<i32> = Vec::new();
+    let mut depth = 0;
+    for i in 0..tokens.len() {
+        if tokens[i].is_opertor_or_keyword("{") {
+            depth += 1;
+            if i > 0 && matches!(&tokens[i - 1], AtExpToken::Text(t) if t == "cases") {
+                depths.push(depth);
+            }
+        } else if tokens[i].is_opertor_or_keyword("}") {
+            if depths.last() == Some(&depth) {
+                depths.pop();
+            }
+            depth -= 1;
+        } else if depths.last() == Some(&depth) {
+            if let AtExpToken::Text(t) = &tokens[i] {
+                if matches!(t.as_str(), ";" | "if" | "otherwise") {
+                    tokens[i] = AtExpToken::OperatorOrKeyword(t.clone());
+                }
+            }
+        }
+    }
+}
+
+pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String> {
+    if !is_cases_opener(tokens, start) {
+        return Ok(None);
+    }
+    let mut cases = Vec::new();
+    let mut row: Vec<AtExpToken> = Vec::new();
+    let mut pos = start + 2;
+    loop {
+        let token = token_from_list(tokens, pos);
+        if token.is_opertor_or_keyword(";") || token.is_opertor_or_keyword("}") {
+            if !row.is_empty() {
+                match to_case(&row) {
+                    Some(case) => cases.push(case),
+                    None => return Ok(None),
+                }
+                row.clear();
+            }
+            if token.is_opertor_or_keyword("}") {
+                break;
+            }
+        } else if let AtExpToken::Error = token {
+            return Ok(None);
+        } else {
+            row.push(token);
+        }
+        pos += 1;
+    }
+    if cases.is_empty() {
+        return Err("Found empty cases in equation.".to_string());
+    }
+    Ok(Some((Rc::new(Cases { cases }), (pos - start + 1) as usize)))
+}
+
+fn to_case(row: &[AtExpToken]) -> Option<Case> {
+    let is = |token: &AtExpToken, keyword: &str| token.is_opertor_or_keyword(keyword);
+    let (value, condition) = match row {
+        [AtExpToken::AstNode(value)] => (value, Condition::None),
+        [AtExpToken::AstNode(value), otherwise] if is(otherwise, "otherwise") => {
+            (value, Condition::Otherwise)
+        }
+        [AtExpToken::AstNode(value), comma, otherwise]
+            if is(comma, ",") && is(otherwise, "otherwise") =>
+        {
+            (value, Condition::Otherwise)
+        }
+        [AtExpToken::AstNode(value), _if, AtExpToken::AstNode(condition)] if is(_if, "if") => {
+            (value, Condition::If(condition.clone()))
+        }
+        [AtExpToken::AstNode(value), comma, _if, AtExpToken::AstNode(condition)]
+            if is(comma, ",") && is(_if, "if") =>
+        {
+            (value, Condition::If(condition.clone()))
+        }
+        [AtExpToken::AstNode(value), comma, AtExpToken::AstNode(condition)] if is(comma, ",") => {
+            (value, Condition::Plain(condition.clone()))
+        }
+        _ => return None,
+    };
+    Some(Case {
+        value: value.clone(),
+        condition,
+    })
+}
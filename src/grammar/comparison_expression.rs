@@ -0,0 +1,110 @@
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::at_expression::AtExpToken;
+use crate::typed_value::TypedValue;
+
+use super::{check_either_side_for_opers, token_from_list};
+
+pub static COMPARISON_OPERATORS: [&str; 6] = ["==", "!=", "<", "<=", ">", ">="];
+
+/// Operators that bind tighter than comparisons.
+pub static ARITHMETIC_OPERATORS: [&str; 7] = ["+", "-", "*", "/", "%", "^", "_"];
+
+pub struct ComparisonExpression {
+    children: (DefAstNode, DefAstNode),
+    operator: String,
+}
+
+impl AstNode for ComparisonExpression {
+    fn evaluate(&self, imported_values: &Vec<TypedValue>) -> Result<TypedValue, String> {
+        let lhs = self.children.0.evaluate(imported_values)?;
+        let rhs = self.children.1.evaluate(imported_values)?;
+        let result = match self.operator.as_str() {
+            "==" => equals(&lhs, &rhs)?,
+            "!=" => !equals(&lhs, &rhs)?,
+            operator => {
+                let ordering = compare(&lhs, &rhs)?;
+                match operator {
+                    "<" => ordering == Ordering::Less,
+                    "<=" => ordering != Ordering::Greater,
+                    ">" => ordering == Ordering::Greater,
+                    _ => ordering != Ordering::Less,
+                }
+            }
+        };
+        Ok(TypedValue::Bool(result))
+    }
+
+    fn codegen(&self) -> String {
+        let operator = match self.operator.as_str() {
+            "==" => "=",
+            "!=" => "\\neq",
+            "<=" => "\\leq",
+            ">=" => "\\geq",
+            operator => operator,
+        };
+        format!(
+            "{} {} {}",
+            self.children.0.codegen(),
+            operator,
+            self.children.1.codegen()
+        )
+    }
+}
+
+fn mismatch(lhs: &TypedValue, rhs: &TypedValue) -> String {
+    format!(
+        "Tried to compare a {} and a {} in @() expression.",
+        lhs.type_name(),
+        rhs.type_name()
+    )
+}
+
+fn equals(lhs: &TypedValue, rhs: &TypedValue) -> Result<bool, String> {
+    Ok(match (lhs, rhs) {
+        (TypedValue::Number(a), TypedValue::Number(b)) => a == b,
+        (TypedValue::String(a), TypedValue::String(b)) => a == b,
+        (TypedValue::Bool(a), TypedValue::Bool(b)) => a == b,
+        (TypedValue::Vector(a), TypedValue::Vector(b)) => a == b,
+        (TypedValue::Matrix(a), TypedValue::Matrix(b)) => a == b,
+        (TypedValue::Colour(a), TypedValue::Colour(b)) => a.html() == b.html(),
+        (TypedValue::Size(_), TypedValue::Size(_)) => compare(lhs, rhs)? == Ordering::Equal,
+        _ => return Err(mismatch(lhs, rhs)),
+    })
+}
+
+fn compare(lhs: &TypedValue, rhs: &TypedValue) -> Result<Ordering, String> {
+    let ordering = match (lhs, rhs) {
+        (TypedValue::Number(a), TypedValue::Number(b)) => a.partial_cmp(b),
+        (TypedValue::String(a), TypedValue::String(b)) => Some(a.cmp(b)),
+        (TypedValue::Size(a), TypedValue::Size(b)) => a.convert_to(b)?.value.partial_cmp(&b.value),
+        _ => return Err(mismatch(lhs, rhs)),
+    };
+    ordering.ok_or_else(|| "Tried to compare NaN in @() expression.".to_string())
+}
+
+pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String> {
+    let operator = match token_from_list(tokens, start + 1) {
+        AtExpToken::OperatorOrKeyword(op) if COMPARISON_OPERATORS.contains(&op.as_str()) => op,
+        _ => return Ok(None),
+    };
+    match (
+        token_from_list(tokens, start),
+        token_from_list(tokens, start + 2),
+    ) {
+        (AtExpToken::AstNode(lhs), AtExpToken::AstNode(rhs))
+            if !check_either_side_for_opers(tokens, start, 3, ARITHMETIC_OPERATORS.to_vec()) =>
+        {
+            Ok(Some((
+                Rc::new(ComparisonExpression {
+                    children: (lhs, rhs),
+                    operator,
+                }),
+                3,
+            )))
+        }
+        _ => Ok(None),
+    }
+}
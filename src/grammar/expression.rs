@@ -4,6 +4,9 @@ use crate::ast::{AstNode, OpAstNode};
 use crate::at_expression::AtExpToken;
 use crate::typed_value::TypedValue;
 
+use super::big_operator::is_big_operator;
+use super::cases::is_cases_opener;
+use super::function_call::is_function_call;
 use super::token_from_list;
 
 enum BracketType {
@@ -35,9 +38,23 @@ impl AstNode for Expression {
             panic!("Expression::codegen() called with non-AstNode token.")
         }
     }
+
+    fn codegen_ungrouped(&self) -> String {
+        if let AtExpToken::AstNode(node) = &self.value {
+            node.codegen_ungrouped()
+        } else {
+            panic!("Expression::codegen_ungrouped() called with non-AstNode token.")
+        }
+    }
 }
 
 pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String> {
+    if is_big_operator(tokens, start - 1)
+        || is_cases_opener(tokens, start - 1)
+        || is_function_call(tokens, start - 1)
+    {
+        return Ok(None);
+    }
     let mut bracket_type: Option<BracketType> = None;
     if token_from_list(tokens, start).is_opertor_or_keyword("(") {
         bracket_type = Some(BracketType::Round);
@@ -0,0 +1,150 @@
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::at_expression::AtExpToken;
+use crate::builtins;
+use crate::colour;
+use crate::linear_algebra;
+use crate::typed_value::TypedValue;
+
+use super::big_operator::BIG_OPERATORS;
+use super::token_from_list;
+
+/// Any number of arguments.
+const VARIADIC: usize = usize::MAX;
+
+/// Builtin functions of @() expressions with their minimum and maximum number of arguments.
+/// `sqrt` and `abs` are big operators.
+static FUNCTIONS: [(&str, usize, usize); 28] = [
+    ("transpose", 1, 1),
+    ("det", 1, 1),
+    ("dot", 2, 2),
+    ("cross", 2, 2),
+    ("rgb", 3, 3),
+    ("hsl", 3, 3),
+    ("mix", 3, 3),
+    ("lighten", 2, 2),
+    ("darken", 2, 2),
+    ("sin", 1, 1),
+    ("cos", 1, 1),
+    ("tan", 1, 1),
+    ("asin", 1, 1),
+    ("acos", 1, 1),
+    ("atan", 1, 1),
+    ("log", 1, 2),
+    ("exp", 1, 1),
+    ("round", 1, 2),
+    ("floor", 1, 1),
+    ("ceil", 1, 1),
+    ("min", 1, VARIADIC),
+    ("max", 1, VARIADIC),
+    ("upper", 1, 1),
+    ("lower", 1, 1),
+    ("len", 1, 1),
+    ("replace", 3, 3),
+    ("substr", 2, 3),
+    ("join", 2, VARIADIC),
+];
+
+pub struct FunctionCall {
+    name: String,
+    args: Vec<DefAstNode>,
+}
+
+impl AstNode for FunctionCall {
+    fn evaluate(&self, imported_values: &Vec<TypedValue>) -> Result<TypedValue, String> {
+        let mut args = Vec::new();
+        for arg in &self.args {
+            args.push(arg.evaluate(imported_values)?);
+        }
+        match self.name.as_str() {
+            "transpose" => linear_algebra::transpose(&args[0]),
+            "det" => Ok(TypedValue::Number(linear_algebra::determinant(&args[0])?)),
+            "dot" => Ok(TypedValue::Number(linear_algebra::dot(&args[0], &args[1])?)),
+            "cross" => linear_algebra::cross(&args[0], &args[1]),
+            "rgb" | "hsl" | "mix" | "lighten" | "darken" => colour::call(&self.name, &args),
+            _ => builtins::call(&self.name, &args),
+        }
+    }
+
+    fn codegen(&self) -> String {
+        let args = self
+            .args
+            .iter()
+            .map(|a| a.codegen())
+            .collect::<Vec<String>>()
+            .join(", ");
+        let name = match self.name.as_str() {
+            "sin" | "cos" | "tan" | "log" | "exp" | "min" | "max" | "det" => {
+                format!("\\{}", self.name)
+            }
+            "asin" | "acos" | "atan" => format!("\\arc{}", &self.name[1..]),
+            _ => format!("\\mathrm{{{}}}", self.name),
+        };
+        format!("{}\\left({}\\right)", name, args)
+    }
+}
+
+/// Every function callable in @() expressions, including big operators.
+pub fn function_names() -> Vec<&'static str> {
+    let mut names: Vec<&str> = FUNCTIONS.iter().map(|(f, _, _)| *f).collect();
+    names.extend(BIG_OPERATORS);
+    names
+}
+
+pub fn is_function_name(name: &str) -> bool {
+    FUNCTIONS.iter().any(|(f, _, _)| *f == name)
+}
+
+/// A builtin function name directly followed by `(`.
+pub fn is_function_call(tokens: &Vec<AtExpToken>, pos: i32) -> bool {
+    matches!(token_from_list(tokens, pos), AtExpToken::OperatorOrKeyword(name) if is_function_name(&name))
+        && token_from_list(tokens, pos + 1).is_opertor_or_keyword("(")
+}
+
+pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String> {
+    if !is_function_call(tokens, start) {
+        return Ok(None);
+    }
+    let name = match token_from_list(tokens, start) {
+        AtExpToken::OperatorOrKeyword(name) => name,
+        _ => return Ok(None),
+    };
+    let mut args = Vec::new();
+    let mut pos = start + 2;
+    loop {
+        match token_from_list(tokens, pos) {
+            AtExpToken::AstNode(node) => args.push(node),
+            t if t.is_opertor_or_keyword(")") && args.is_empty() => break,
+            _ => return Ok(None),
+        }
+        pos += 1;
+        let separator = token_from_list(tokens, pos);
+        if separator.is_opertor_or_keyword(")") {
+            break;
+        } else if !separator.is_opertor_or_keyword(",") {
+            return Ok(None);
+        }
+        pos += 1;
+    }
+    let (_, min, max) = FUNCTIONS.iter().find(|(f, _, _)| *f == name).unwrap();
+    if args.len() < *min || args.len() > *max {
+        let expected = if min == max {
+            min.to_string()
+        } else if *max == VARIADIC {
+            format!("at least {}", min)
+        } else {
+            format!("{} to {}", min, max)
+        };
+        return Err(format!(
+            "Function {}() in @() expression takes {} arguments, but {} were given.",
+            name,
+            expected,
+            args.len()
+        ));
+    }
+    Ok(Some((
+        Rc::new(FunctionCall { name, args }),
+        (pos - start + 1) as usize,
+    )))
+}
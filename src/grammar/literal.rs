@@ -18,6 +18,7 @@ impl AstNode for Literal {
         match &self.value {
             TypedValue::Number(n) => format!("{n}"),
             TypedValue::String(s) => format!("\"{s}\""),
+            value => value.stringify(),
         }
     }
 }
@@ -31,9 +32,9 @@ pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String>
                 }),
                 1,
             ))),
-            TypedValue::String(s) => Ok(Some((
+            value => Ok(Some((
                 Rc::new(Literal {
-                    value: TypedValue::String(s.clone()),
+                    value: value.clone(),
                 }),
                 1,
             ))),
@@ -0,0 +1,123 @@
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::at_expression::AtExpToken;
+use crate::typed_value::TypedValue;
+
+use super::comparison_expression::{ARITHMETIC_OPERATORS, COMPARISON_OPERATORS};
+use super::{check_either_side_for_opers, token_from_list};
+
+enum Operation {
+    And,
+    Or,
+    Not,
+}
+
+/// `a and b`, `a or b` and `not a`. The right side of `and` and `or` is only evaluated when needed.
+pub struct LogicalExpression {
+    children: Vec<DefAstNode>,
+    operation: Operation,
+}
+
+impl AstNode for LogicalExpression {
+    fn evaluate(&self, imported_values: &Vec<TypedValue>) -> Result<TypedValue, String> {
+        let operand = |i: usize| -> Result<bool, String> {
+            match self.children[i].evaluate(imported_values)? {
+                TypedValue::Bool(b) => Ok(b),
+                value => Err(format!(
+                    "Expected a Bool for \"{}\" in @() expression, found {}.",
+                    self.keyword(),
+                    value.type_name()
+                )),
+            }
+        };
+        let result = match self.operation {
+            Operation::And => operand(0)? && operand(1)?,
+            Operation::Or => operand(0)? || operand(1)?,
+            Operation::Not => !operand(0)?,
+        };
+        Ok(TypedValue::Bool(result))
+    }
+
+    fn codegen(&self) -> String {
+        match self.operation {
+            Operation::And => format!(
+                "{} \\land {}",
+                self.children[0].codegen(),
+                self.children[1].codegen()
+            ),
+            Operation::Or => format!(
+                "{} \\lor {}",
+                self.children[0].codegen(),
+                self.children[1].codegen()
+            ),
+            Operation::Not => format!("\\lnot {}", self.children[0].codegen()),
+        }
+    }
+}
+
+impl LogicalExpression {
+    fn keyword(&self) -> &str {
+        match self.operation {
+            Operation::And => "and",
+            Operation::Or => "or",
+            Operation::Not => "not",
+        }
+    }
+}
+
+/// Operators that bind tighter than `operator`, `not` before `and` before `or`.
+fn tighter_operators(operator: &str) -> Vec<&'static str> {
+    let mut operators = [ARITHMETIC_OPERATORS.as_slice(), &COMPARISON_OPERATORS].concat();
+    match operator {
+        "or" => operators.extend(["not", "and"]),
+        "and" => operators.push("not"),
+        _ => {}
+    }
+    operators
+}
+
+pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String> {
+    if token_from_list(tokens, start).is_opertor_or_keyword("not") {
+        return match token_from_list(tokens, start + 1) {
+            AtExpToken::AstNode(operand)
+                if !check_either_side_for_opers(tokens, start + 1, 1, tighter_operators("not")) =>
+            {
+                Ok(Some((
+                    Rc::new(LogicalExpression {
+                        children: vec![operand],
+                        operation: Operation::Not,
+                    }),
+                    2,
+                )))
+            }
+            _ => Ok(None),
+        };
+    }
+    let operation = match token_from_list(tokens, start + 1) {
+        t if t.is_opertor_or_keyword("and") => Operation::And,
+        t if t.is_opertor_or_keyword("or") => Operation::Or,
+        _ => return Ok(None),
+    };
+    let keyword = match operation {
+        Operation::And => "and",
+        _ => "or",
+    };
+    match (
+        token_from_list(tokens, start),
+        token_from_list(tokens, start + 2),
+    ) {
+        (AtExpToken::AstNode(lhs), AtExpToken::AstNode(rhs))
+            if !check_either_side_for_opers(tokens, start, 3, tighter_operators(keyword)) =>
+        {
+            Ok(Some((
+                Rc::new(LogicalExpression {
+                    children: vec![lhs, rhs],
+                    operation,
+                }),
+                3,
+            )))
+        }
+        _ => Ok(None),
+    }
+}
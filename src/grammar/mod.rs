@@ -1,11 +1,20 @@
 use super::{ast::OpAstNode, at_expression::AtExpToken};
 
+pub mod big_operator;
 pub mod binary_additive_expression;
 pub mod binary_multiplicative_expression;
 pub mod binary_pow_expression;
+pub mod cases;
+pub mod comparison_expression;
 pub mod expression;
+pub mod function_call;
 pub mod imported_value;
 pub mod literal;
+pub mod logical_expression;
+pub mod subscript_expression;
+pub mod symbols;
+pub mod template_string;
+pub mod ternary_expression;
 pub mod text;
 pub mod text_node_pair;
 pub mod unary_additive_expression;
@@ -14,6 +23,8 @@ pub mod vector;
 pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String> {
     if let Some(r) = binary_additive_expression::parse(tokens, start)? {
         Ok(Some(r))
+    } else if let Some(r) = subscript_expression::parse(tokens, start)? {
+        Ok(Some(r))
     } else if let Some(r) = binary_pow_expression::parse(tokens, start)? {
         Ok(Some(r))
     } else if let Some(r) = binary_multiplicative_expression::parse(tokens, start)? {
@@ -26,8 +37,20 @@ pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String>
         Ok(Some(r))
     } else if let Some(r) = unary_additive_expression::parse(tokens, start)? {
         Ok(Some(r))
+    } else if let Some(r) = comparison_expression::parse(tokens, start)? {
+        Ok(Some(r))
+    } else if let Some(r) = logical_expression::parse(tokens, start)? {
+        Ok(Some(r))
+    } else if let Some(r) = ternary_expression::parse(tokens, start)? {
+        Ok(Some(r))
     } else if let Some(r) = vector::parse(tokens, start)? {
         Ok(Some(r))
+    } else if let Some(r) = big_operator::parse(tokens, start)? {
+        Ok(Some(r))
+    } else if let Some(r) = function_call::parse(tokens, start)? {
+        Ok(Some(r))
+    } else if let Some(r) = cases::parse(tokens, start)? {
+        Ok(Some(r))
     } else if let Some(r) = text::parse(tokens, start)? {
         Ok(Some(r))
     } else if let Some(r) = text_node_pair::parse(tokens, start)? {
@@ -0,0 +1,84 @@
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::at_expression::AtExpToken;
+use crate::typed_value::TypedValue;
+
+use super::token_from_list;
+
+/// A subscript with an optional superscript, e.g. `x_i` or `x_i^2`.
+pub struct SubscriptExpression {
+    base: DefAstNode,
+    /// Scripts in the order they were written, `_` or `^` with their operand.
+    scripts: Vec<(String, DefAstNode)>,
+}
+
+#[allow(unused)]
+impl AstNode for SubscriptExpression {
+    fn evaluate(&self, imported_values: &Vec<TypedValue>) -> Result<TypedValue, String> {
+        Err("Can't evaluate subscripts in @() expression.".to_string())
+    }
+
+    fn codegen(&self) -> String {
+        let mut code = self.base.codegen();
+        for (op, operand) in &self.scripts {
+            code.push_str(op);
+            code.push_str(&script_operand(operand));
+        }
+        code
+    }
+}
+
+/// Braces the operand of `_` or `^` unless it is a single character.
+pub fn script_operand(node: &DefAstNode) -> String {
+    let code = node.codegen_ungrouped();
+    if code.chars().count() == 1 {
+        code
+    } else {
+        format!("{{{}}}", code)
+    }
+}
+
+fn script_op(tokens: &Vec<AtExpToken>, pos: i32) -> Option<&'static str> {
+    if token_from_list(tokens, pos).is_opertor_or_keyword("_") {
+        Some("_")
+    } else if token_from_list(tokens, pos).is_opertor_or_keyword("^") {
+        Some("^")
+    } else {
+        None
+    }
+}
+
+fn node(tokens: &Vec<AtExpToken>, pos: i32) -> Option<DefAstNode> {
+    match token_from_list(tokens, pos) {
+        AtExpToken::AstNode(node) => Some(node),
+        _ => None,
+    }
+}
+
+pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String> {
+    let base = match node(tokens, start) {
+        Some(base) => base,
+        None => return Ok(None),
+    };
+    let first_op = match script_op(tokens, start + 1) {
+        Some(op) => op,
+        None => return Ok(None),
+    };
+    let first = match node(tokens, start + 2) {
+        Some(first) => first,
+        None => return Ok(None),
+    };
+    let mut scripts = vec![(first_op.to_string(), first)];
+    match script_op(tokens, start + 3) {
+        Some(second_op) if second_op != first_op => match node(tokens, start + 4) {
+            Some(second) => scripts.push((second_op.to_string(), second)),
+            None => return Ok(None),
+        },
+        // A lone `^` is a power.
+        _ if first_op == "^" => return Ok(None),
+        _ => {}
+    }
+    let len = scripts.len() * 2 + 1;
+    Ok(Some((Rc::new(SubscriptExpression { base, scripts }), len)))
+}
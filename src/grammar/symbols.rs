@@ -0,0 +1,431 @@
+// Shorthands replaced inside equations. This table also generates the table
+// in the docs and the regex used by the syntax highlighter.
+
+pub struct TxtRpl {
+    pub text: &'static str,
+    pub replacment: &'static str,
+}
+
+const fn rpl(text: &'static str, replacment: &'static str) -> TxtRpl {
+    TxtRpl { text, replacment }
+}
+
+pub static REPLACMENTS: &[TxtRpl] = &[
+    // Relations and arrows
+    rpl("<->", "\\leftrightarrow"),
+    rpl("<=>", "\\leftrightharpoons"),
+    rpl("<=", "\\le"),
+    rpl(">=", "\\ge"),
+    rpl("+-", "\\pm"),
+    rpl("-+", "\\mp"),
+    rpl("=>", "\\implies"),
+    rpl("!=", "\\ne"),
+    rpl("->", "\\rightarrow"),
+    rpl("<-", "\\leftarrow"),
+    rpl("^^", "\\uparrow"),
+    rpl("~==", "\\cong"),
+    rpl("~=", "\\simeq"),
+    rpl("~~", "\\approx"),
+    rpl("iff", "\\iff"),
+    // Functions
+    rpl("arcsin", "\\arcsin"),
+    rpl("arccos", "\\arccos"),
+    rpl("arctan", "\\arctan"),
+    rpl("arcsinh", "\\mathrm{arcsinh}"),
+    rpl("arccosh", "\\mathrm{arccosh}"),
+    rpl("arctanh", "\\mathrm{arctanh}"),
+    rpl("sinh", "\\sinh"),
+    rpl("cosh", "\\cosh"),
+    rpl("tanh", "\\tanh"),
+    rpl("coth", "\\coth"),
+    rpl("sin", "\\sin"),
+    rpl("cos", "\\cos"),
+    rpl("tan", "\\tan"),
+    rpl("cot", "\\cot"),
+    rpl("sec", "\\sec"),
+    rpl("csc", "\\csc"),
+    rpl("log", "\\log"),
+    rpl("ln", "\\ln"),
+    rpl("exp", "\\exp"),
+    rpl("det", "\\det"),
+    rpl("gcd", "\\gcd"),
+    rpl("min", "\\min"),
+    rpl("max", "\\max"),
+    // Greek letters
+    rpl("alpha", "\\alpha"),
+    rpl("beta", "\\beta"),
+    rpl("gamma", "\\gamma"),
+    rpl("Gamma", "\\Gamma"),
+    rpl("delta", "\\delta"),
+    rpl("Delta", "\\Delta"),
+    rpl("epsilon", "\\epsilon"),
+    rpl("varepsilon", "\\varepsilon"),
+    rpl("zeta", "\\zeta"),
+    rpl("eta", "\\eta"),
+    rpl("theta", "\\theta"),
+    rpl("vartheta", "\\vartheta"),
+    rpl("Theta", "\\Theta"),
+    rpl("iota", "\\iota"),
+    rpl("kappa", "\\kappa"),
+    rpl("lambda", "\\lambda"),
+    rpl("Lambda", "\\Lambda"),
+    rpl("mu", "\\mu"),
+    rpl("nu", "\\nu"),
+    rpl("xi", "\\xi"),
+    rpl("Xi", "\\Xi"),
+    rpl("pi", "\\pi"),
+    rpl("varpi", "\\varpi"),
+    rpl("Pi", "\\Pi"),
+    rpl("rho", "\\rho"),
+    rpl("varrho", "\\varrho"),
+    rpl("sigma", "\\sigma"),
+    rpl("varsigma", "\\varsigma"),
+    rpl("Sigma", "\\Sigma"),
+    rpl("tau", "\\tau"),
+    rpl("upsilon", "\\upsilon"),
+    rpl("Upsilon", "\\Upsilon"),
+    rpl("phi", "\\phi"),
+    rpl("varphi", "\\varphi"),
+    rpl("Phi", "\\Phi"),
+    rpl("chi", "\\chi"),
+    rpl("psi", "\\psi"),
+    rpl("Psi", "\\Psi"),
+    rpl("omega", "\\omega"),
+    rpl("Omega", "\\Omega"),
+    // Calculus and logic
+    rpl("inf", "\\infty"),
+    rpl("nabla", "\\nabla"),
+    rpl("partial", "\\partial"),
+    rpl("forall", "\\forall"),
+    rpl("exists", "\\exists"),
+    rpl("nexists", "\\nexists"),
+    rpl("neg", "\\neg"),
+    rpl("land", "\\land"),
+    rpl("lor", "\\lor"),
+    // Sets
+    rpl("in", "\\in"),
+    rpl("notin", "\\notin"),
+    rpl("subset", "\\subset"),
+    rpl("subseteq", "\\subseteq"),
+    rpl("supset", "\\supset"),
+    rpl("supseteq", "\\supseteq"),
+    rpl("cup", "\\cup"),
+    rpl("cap", "\\cap"),
+    rpl("setminus", "\\setminus"),
+    rpl("emptyset", "\\emptyset"),
+];
+
+/// Unicode math input, e.g. from a compose key. Each of these is its own token.
+pub static UNICODE_REPLACMENTS: &[TxtRpl] = &[
+    rpl("α", "\\alpha"),
+    rpl("β", "\\beta"),
+    rpl("γ", "\\gamma"),
+    rpl("δ", "\\delta"),
+    rpl("ϵ", "\\epsilon"),
+    rpl("ε", "\\varepsilon"),
+    rpl("ζ", "\\zeta"),
+    rpl("η", "\\eta"),
+    rpl("θ", "\\theta"),
+    rpl("ϑ", "\\vartheta"),
+    rpl("ι", "\\iota"),
+    rpl("κ", "\\kappa"),
+    rpl("λ", "\\lambda"),
+    rpl("μ", "\\mu"),
+    rpl("ν", "\\nu"),
+    rpl("ξ", "\\xi"),
+    rpl("π", "\\pi"),
+    rpl("ϖ", "\\varpi"),
+    rpl("ρ", "\\rho"),
+    rpl("ϱ", "\\varrho"),
+    rpl("σ", "\\sigma"),
+    rpl("ς", "\\varsigma"),
+    rpl("τ", "\\tau"),
+    rpl("υ", "\\upsilon"),
+    rpl("ϕ", "\\phi"),
+    rpl("φ", "\\varphi"),
+    rpl("χ", "\\chi"),
+    rpl("ψ", "\\psi"),
+    rpl("ω", "\\omega"),
+    rpl("Γ", "\\Gamma"),
+    rpl("Δ", "\\Delta"),
+    rpl("Θ", "\\Theta"),
+    rpl("Λ", "\\Lambda"),
+    rpl("Ξ", "\\Xi"),
+    rpl("Π", "\\Pi"),
+    rpl("Σ", "\\Sigma"),
+    rpl("Υ", "\\Upsilon"),
+    rpl("Φ", "\\Phi"),
+    rpl("Ψ", "\\Psi"),
+    rpl("Ω", "\\Omega"),
+    rpl("≤", "\\le"),
+    rpl("≥", "\\ge"),
+    rpl("≠", "\\ne"),
+    rpl("≈", "\\approx"),
+    rpl("≡", "\\equiv"),
+    rpl("≅", "\\cong"),
+    rpl("≃", "\\simeq"),
+    rpl("∝", "\\propto"),
+    rpl("±", "\\pm"),
+    rpl("∓", "\\mp"),
+    rpl("×", "\\times"),
+    rpl("·", "\\cdot"),
+    rpl("÷", "\\div"),
+    rpl("∘", "\\circ"),
+    rpl("→", "\\rightarrow"),
+    rpl("←", "\\leftarrow"),
+    rpl("↔", "\\leftrightarrow"),
+    rpl("⇒", "\\implies"),
+    rpl("⇐", "\\impliedby"),
+    rpl("⇔", "\\iff"),
+    rpl("↦", "\\mapsto"),
+    rpl("↑", "\\uparrow"),
+    rpl("↓", "\\downarrow"),
+    rpl("∑", "\\sum"),
+    rpl("∏", "\\prod"),
+    rpl("∫", "\\int"),
+    rpl("∬", "\\iint"),
+    rpl("∮", "\\oint"),
+    rpl("√", "\\sqrt"),
+    rpl("∞", "\\infty"),
+    rpl("∂", "\\partial"),
+    rpl("∇", "\\nabla"),
+    rpl("∀", "\\forall"),
+    rpl("∃", "\\exists"),
+    rpl("∄", "\\nexists"),
+    rpl("¬", "\\neg"),
+    rpl("∧", "\\land"),
+    rpl("∨", "\\lor"),
+    rpl("∈", "\\in"),
+    rpl("∉", "\\notin"),
+    rpl("⊂", "\\subset"),
+    rpl("⊆", "\\subseteq"),
+    rpl("⊃", "\\supset"),
+    rpl("⊇", "\\supseteq"),
+    rpl("∪", "\\cup"),
+    rpl("∩", "\\cap"),
+    rpl("∖", "\\setminus"),
+    rpl("∅", "\\emptyset"),
+    rpl("ℏ", "\\hbar"),
+    rpl("ℓ", "\\ell"),
+    rpl("ℕ", "\\mathbb{N}"),
+    rpl("ℤ", "\\mathbb{Z}"),
+    rpl("ℚ", "\\mathbb{Q}"),
+    rpl("ℝ", "\\mathbb{R}"),
+    rpl("ℂ", "\\mathbb{C}"),
+];
+
+/// Plain text Unicode that pdflatex can't handle without `inputenc`.
+pub static UNICODE_TEXT_REPLACMENTS: &[TxtRpl] = &[
+    rpl("à", "\\`{a}"),
+    rpl("è", "\\`{e}"),
+    rpl("ì", "\\`{i}"),
+    rpl("ò", "\\`{o}"),
+    rpl("ù", "\\`{u}"),
+    rpl("À", "\\`{A}"),
+    rpl("È", "\\`{E}"),
+    rpl("Ì", "\\`{I}"),
+    rpl("Ò", "\\`{O}"),
+    rpl("Ù", "\\`{U}"),
+    rpl("á", "\\'{a}"),
+    rpl("é", "\\'{e}"),
+    rpl("í", "\\'{i}"),
+    rpl("ó", "\\'{o}"),
+    rpl("ú", "\\'{u}"),
+    rpl("ý", "\\'{y}"),
+    rpl("Á", "\\'{A}"),
+    rpl("É", "\\'{E}"),
+    rpl("Í", "\\'{I}"),
+    rpl("Ó", "\\'{O}"),
+    rpl("Ú", "\\'{U}"),
+    rpl("Ý", "\\'{Y}"),
+    rpl("â", "\\^{a}"),
+    rpl("ê", "\\^{e}"),
+    rpl("î", "\\^{i}"),
+    rpl("ô", "\\^{o}"),
+    rpl("û", "\\^{u}"),
+    rpl("Â", "\\^{A}"),
+    rpl("Ê", "\\^{E}"),
+    rpl("Î", "\\^{I}"),
+    rpl("Ô", "\\^{O}"),
+    rpl("Û", "\\^{U}"),
+    rpl("ä", "\\\"{a}"),
+    rpl("ë", "\\\"{e}"),
+    rpl("ï", "\\\"{i}"),
+    rpl("ö", "\\\"{o}"),
+    rpl("ü", "\\\"{u}"),
+    rpl("ÿ", "\\\"{y}"),
+    rpl("Ä", "\\\"{A}"),
+    rpl("Ë", "\\\"{E}"),
+    rpl("Ï", "\\\"{I}"),
+    rpl("Ö", "\\\"{O}"),
+    rpl("Ü", "\\\"{U}"),
+    rpl("ã", "\\~{a}"),
+    rpl("ñ", "\\~{n}"),
+    rpl("õ", "\\~{o}"),
+    rpl("Ã", "\\~{A}"),
+    rpl("Ñ", "\\~{N}"),
+    rpl("Õ", "\\~{O}"),
+    rpl("ç", "\\c{c}"),
+    rpl("Ç", "\\c{C}"),
+    rpl("å", "\\r{a}"),
+    rpl("Å", "\\r{A}"),
+    rpl("ß", "\\ss{}"),
+    rpl("æ", "\\ae{}"),
+    rpl("Æ", "\\AE{}"),
+    rpl("ø", "\\o{}"),
+    rpl("Ø", "\\O{}"),
+    rpl("œ", "\\oe{}"),
+    rpl("Œ", "\\OE{}"),
+    rpl("–", "--"),
+    rpl("—", "---"),
+    rpl("‘", "`"),
+    rpl("’", "'"),
+    rpl("“", "``"),
+    rpl("”", "''"),
+    rpl("…", "\\ldots{}"),
+    rpl(" ", "~"),
+    rpl("°", "\\textdegree{}"),
+    rpl("€", "\\texteuro{}"),
+    rpl("£", "\\pounds{}"),
+    rpl("©", "\\copyright{}"),
+];
+
+fn is_word(text: &str) -> bool {
+    text.chars().all(|c| c.is_alphabetic())
+}
+
+fn lookup(text: &str) -> Option<&'static str> {
+    REPLACMENTS
+        .iter()
+        .chain(UNICODE_REPLACMENTS.iter())
+        .find(|rpl| rpl.text == text)
+        .map(|rpl| rpl.replacment)
+}
+
+fn lookup_char(table: &'static [TxtRpl], c: char) -> Option<&'static str> {
+    let mut buffer = [0; 4];
+    let text = c.encode_utf8(&mut buffer);
+    table
+        .iter()
+        .find(|rpl| rpl.text == text)
+        .map(|rpl| rpl.replacment)
+}
+
+pub fn unicode_math_replacment(c: char) -> Option<&'static str> {
+    lookup_char(UNICODE_REPLACMENTS, c)
+}
+
+pub fn is_unicode_math(c: char) -> bool {
+    !c.is_ascii() && unicode_math_replacment(c).is_some()
+}
+
+/// Package needed for a replacment to compile.
+pub fn required_package(replacment: &str) -> Option<&'static str> {
+    if replacment.starts_with("\\mathbb") {
+        Some("amssymb")
+    } else {
+        None
+    }
+}
+
+/// Replaces Unicode in plain text with pdflatex safe TeX. Math symbols are
+/// wrapped in `\ensuremath`.
+pub fn text_to_ascii(text: &str) -> String {
+    let mut result = String::new();
+    for c in text.chars() {
+        if c.is_ascii() {
+            result.push(c);
+        } else if let Some(replacment) = lookup_char(UNICODE_TEXT_REPLACMENTS, c) {
+            result.push_str(replacment);
+        } else if let Some(replacment) = unicode_math_replacment(c) {
+            result.push_str(&format!("\\ensuremath{{{}}}", replacment));
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Replaces shorthands that make up a whole word, so `sin` in `using` or `cosh` in
+/// `arccosh` are left alone. Runs of symbols are split into the longest shorthands.
+pub fn do_replacements(text: &str) -> String {
+    if text.starts_with('\\') {
+        return text.to_string();
+    }
+    let mut result = String::new();
+    let mut run = String::new();
+    for c in text.chars() {
+        if !run.is_empty() && is_word(&run) != c.is_alphabetic() {
+            push_run(&mut result, &run);
+            run.clear();
+        }
+        run.push(c);
+    }
+    push_run(&mut result, &run);
+    result
+}
+
+fn push_run(result: &mut String, run: &str) {
+    if is_word(run) {
+        result.push_str(lookup(run).unwrap_or(run));
+        return;
+    }
+    let mut rest = run;
+    while let Some(c) = rest.chars().next() {
+        let longest = REPLACMENTS
+            .iter()
+            .chain(UNICODE_REPLACMENTS.iter())
+            .filter(|rpl| !is_word(rpl.text) && rest.starts_with(rpl.text))
+            .max_by_key(|rpl| rpl.text.len());
+        match longest {
+            Some(rpl) => {
+                result.push_str(rpl.replacment);
+                rest = &rest[rpl.text.len()..];
+            }
+            None => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+}
+
+pub fn generate_docs() -> String {
+    let mut result = "| Token | Replacment | LaTeX |\n|-|-|-|\n".to_string();
+    for rpl in REPLACMENTS.iter().chain(UNICODE_REPLACMENTS.iter()) {
+        result = format!(
+            "{}| `{}` | `{}` | ${}$ |\n",
+            result, rpl.text, rpl.replacment, rpl.replacment
+        );
+    }
+    result
+}
+
+/// Symbols are matched anywhere and words only on word boundaries.
+pub fn generate_regex() -> String {
+    let symbols = REPLACMENTS
+        .iter()
+        .filter(|rpl| !is_word(rpl.text))
+        .map(|rpl| do_regex_escapes(rpl.text))
+        .collect::<Vec<String>>()
+        .join("|");
+    let words = REPLACMENTS
+        .iter()
+        .filter(|rpl| is_word(rpl.text))
+        .map(|rpl| rpl.text)
+        .collect::<Vec<&str>>();
+    format!("({}|\\b(?:{})\\b)", symbols, words.join("|"))
+}
+
+static ESCAPES: [char; 2] = ['^', '+'];
+
+fn do_regex_escapes(s: &str) -> String {
+    let mut result = String::new();
+    for c in s.chars() {
+        if ESCAPES.contains(&c) {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
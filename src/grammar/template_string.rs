@@ -0,0 +1,141 @@
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::at_expression::{parse_at_exprssion, AtExpToken};
+use crate::number_format::{format_value, NumberFormat};
+use crate::parse::CompilerGlobals;
+use crate::parser_modules::variables::var_definition::LiaVarName;
+use crate::token::{Location, Token};
+use crate::tokenize::to_tokens;
+use crate::typed_value::TypedValue;
+
+enum Part {
+    Text(String),
+    Expression(DefAstNode, Option<NumberFormat>),
+}
+
+/// A string literal with expressions in braces, `"Figure {n}: {title}"`.
+pub struct TemplateString {
+    parts: Vec<Part>,
+}
+
+impl AstNode for TemplateString {
+    fn evaluate(&self, imported_values: &Vec<TypedValue>) -> Result<TypedValue, String> {
+        let mut text = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(t) => text.push_str(t),
+                Part::Expression(node, format) => {
+                    text.push_str(&format_value(
+                        &node.evaluate(imported_values)?,
+                        format.as_ref(),
+                    )?);
+                }
+            }
+        }
+        Ok(TypedValue::String(text))
+    }
+
+    fn codegen(&self) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(t) => format!("\\text{{{}}}", t),
+                Part::Expression(node, _) => node.codegen(),
+            })
+            .collect()
+    }
+}
+
+/// Turns a quoted string literal into a string, or a template string if it has `{}`.
+/// `\"`, `\{`, `\}` and `\\` escape a quote, brace or backslash. Braces stay escaped so TeX
+/// prints them instead of reading a group.
+pub fn parse_string_literal(
+    literal: &str,
+    location: Location,
+    lia_variables: &[LiaVarName],
+    globals: &CompilerGlobals,
+) -> Result<AtExpToken, String> {
+    let content = &literal[1..literal.len() - 1];
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = content.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, e)) if matches!(e, '"' | '\\') => text.push(e),
+                Some((_, e)) if matches!(e, '{' | '}') => {
+                    text.push('\\');
+                    text.push(e);
+                }
+                Some((_, e)) => {
+                    text.push(c);
+                    text.push(e);
+                }
+                None => text.push(c),
+            },
+            '{' => {
+                let mut depth = 1;
+                let end = loop {
+                    match chars.next() {
+                        Some((_, '{')) => depth += 1,
+                        Some((j, '}')) => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break j;
+                            }
+                        }
+                        Some(_) => {}
+                        None => {
+                            return Err(format!(
+                                "{} Unclosed \"{{\" in string, write \\{{ for a brace.",
+                                location.stringify()
+                            ))
+                        }
+                    }
+                };
+                let expression = &content[i + 1..end];
+                // Column of the expression in the document, after the opening quote.
+                let column = location.column + 1 + content[..i + 1].chars().count();
+                let tokens = to_tokens(expression.to_string())
+                    .into_iter()
+                    .filter_map(|t| match t {
+                        Token::Misc(s, l) | Token::TexCommand(s, l) => Some(Token::Misc(
+                            s,
+                            Location {
+                                line: location.line,
+                                column: column + l.column - 1,
+                            },
+                        )),
+                        _ => None,
+                    })
+                    .collect::<Vec<Token>>();
+                if tokens.is_empty() {
+                    return Err(format!(
+                        "{} Found empty \"{{}}\" in string, write \\{{\\}} for braces.",
+                        location.stringify()
+                    ));
+                }
+                let ast = parse_at_exprssion(&tokens, lia_variables.to_vec(), globals)?;
+                parts.push(Part::Text(std::mem::take(&mut text)));
+                parts.push(Part::Expression(
+                    ast.root_node.ok_or("Found empty expression in string.")?,
+                    ast.number_format,
+                ));
+            }
+            '}' => {
+                return Err(format!(
+                    "{} Unmatched \"}}\" in string, write \\}} for a brace.",
+                    location.stringify()
+                ))
+            }
+            _ => text.push(c),
+        }
+    }
+    if parts.is_empty() {
+        return Ok(AtExpToken::Literal(TypedValue::String(text)));
+    }
+    parts.push(Part::Text(text));
+    parts.retain(|p| !matches!(p, Part::Text(t) if t.is_empty()));
+    Ok(AtExpToken::AstNode(Rc::new(TemplateString { parts })))
+}
@@ -0,0 +1,72 @@
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::at_expression::AtExpToken;
+use crate::typed_value::TypedValue;
+
+use super::comparison_expression::{ARITHMETIC_OPERATORS, COMPARISON_OPERATORS};
+use super::{check_either_side_for_opers, token_from_list};
+
+/// `condition ? a : b`, only the chosen branch is evaluated.
+pub struct TernaryExpression {
+    condition: DefAstNode,
+    branches: (DefAstNode, DefAstNode),
+}
+
+impl AstNode for TernaryExpression {
+    fn evaluate(&self, imported_values: &Vec<TypedValue>) -> Result<TypedValue, String> {
+        match self.condition.evaluate(imported_values)? {
+            TypedValue::Bool(true) => self.branches.0.evaluate(imported_values),
+            TypedValue::Bool(false) => self.branches.1.evaluate(imported_values),
+            value => Err(format!(
+                "Expected a Bool before \"?\" in @() expression, found {}.",
+                value.type_name()
+            )),
+        }
+    }
+
+    fn codegen(&self) -> String {
+        format!(
+            "\\begin{{cases}} {} & \\text{{if }} {} \\\\ {} & \\text{{otherwise}} \\end{{cases}}",
+            self.branches.0.codegen(),
+            self.condition.codegen(),
+            self.branches.1.codegen()
+        )
+    }
+}
+
+pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String> {
+    if !token_from_list(tokens, start + 1).is_opertor_or_keyword("?")
+        || !token_from_list(tokens, start + 3).is_opertor_or_keyword(":")
+    {
+        return Ok(None);
+    }
+    // Everything else binds tighter, and `a ? b : c ? d : e` groups from the right.
+    let tighter = [
+        ARITHMETIC_OPERATORS.as_slice(),
+        COMPARISON_OPERATORS.as_slice(),
+        &["and", "or", "not"],
+    ]
+    .concat();
+    if check_either_side_for_opers(tokens, start, 5, tighter)
+        || token_from_list(tokens, start + 5).is_opertor_or_keyword("?")
+    {
+        return Ok(None);
+    }
+    match (
+        token_from_list(tokens, start),
+        token_from_list(tokens, start + 2),
+        token_from_list(tokens, start + 4),
+    ) {
+        (AtExpToken::AstNode(condition), AtExpToken::AstNode(a), AtExpToken::AstNode(b)) => {
+            Ok(Some((
+                Rc::new(TernaryExpression {
+                    condition,
+                    branches: (a, b),
+                }),
+                5,
+            )))
+        }
+        _ => Ok(None),
+    }
+}
@@ -39,7 +39,7 @@ impl AstNode for TextNodePair {
 pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String> {
     if token_from_list(tokens, start).is_ast_node()
         && token_from_list(tokens, start + 1).is_ast_node()
-        && !check_either_side_for_opers(tokens, start, 2, vec!["+", "-", "*", "/", "%", "^"])
+        && !check_either_side_for_opers(tokens, start, 2, vec!["+", "-", "*", "/", "%", "^", "_"])
     {
         Ok(Some((
             Rc::new(TextNodePair {
@@ -27,6 +27,10 @@ impl AstNode for UnaryAdditiveExpression {
                 }
                 Operation::Minus => match child_value {
                     TypedValue::Number(i) => Ok(TypedValue::Number(-i)),
+                    TypedValue::Vector(_) | TypedValue::Matrix(_) => {
+                        Ok(crate::linear_algebra::scale(&child_value, -1.0))
+                    }
+                    TypedValue::Size(s) => Ok(TypedValue::Size(s.scale(-1.0))),
                     _ => Err(format!(
                         "Cannot use unary minus on type {}.",
                         child_value.type_name()
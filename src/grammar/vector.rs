@@ -1,86 +1,244 @@
 use std::rc::Rc;
 
-use crate::ast::{AstNode, OpAstNode};
+use crate::ast::{AstNode, DefAstNode, OpAstNode};
 use crate::at_expression::AtExpToken;
+use crate::linear_algebra;
 use crate::typed_value::TypedValue;
 
 use super::token_from_list;
 
+static MATRIX_VARIANTS: [&str; 5] = ["pmatrix", "bmatrix", "Bmatrix", "vmatrix", "Vmatrix"];
+
+#[derive(Clone)]
+pub enum Cell {
+    Node(DefAstNode),
+    /// `...`, `\cdots` or `\ddots` in a row of `:`.
+    HorizontalDots,
+    /// `:`, `\vdots`.
+    VerticalDots,
+}
+
+#[derive(Clone)]
 pub struct Vector_ {
-    children: Vec<AtExpToken>,
+    rows: Vec<Vec<Cell>>,
+    /// Number of columns left of the divider of an augmented matrix.
+    divider: Option<usize>,
+    /// Explicit environment, e.g. `bmatrix[1, 2]`.
+    variant: Option<String>,
 }
 
 impl AstNode for Vector_ {
-    fn evaluate(&self, _imported_values: &Vec<TypedValue>) -> Result<TypedValue, String> {
-        Err("Can't evaluate vectors. Vector arithmetic yet supported.".to_string())
+    fn evaluate(&self, imported_values: &Vec<TypedValue>) -> Result<TypedValue, String> {
+        if self.divider.is_some() {
+            return Err("Can't evaluate augmented matrices in @() expression.".to_string());
+        }
+        if self.rows.is_empty() {
+            return Ok(TypedValue::Vector(Vec::new()));
+        }
+        let mut rows = Vec::new();
+        for row in &self.rows {
+            let mut values = Vec::new();
+            for cell in row {
+                match cell {
+                    Cell::Node(node) => match node.evaluate(imported_values)? {
+                        TypedValue::Number(n) => values.push(n),
+                        value => {
+                            return Err(format!(
+                                "Elements of vectors must be numbers in @() expression, found {}.",
+                                value.type_name()
+                            ))
+                        }
+                    },
+                    _ => {
+                        return Err(
+                            "Can't evaluate ellipses in vectors in @() expression.".to_string()
+                        )
+                    }
+                }
+            }
+            rows.push(values);
+        }
+        linear_algebra::from_rows(rows)
     }
 
     fn codegen(&self) -> String {
-        let mut code = String::new();
-        code.push_str("\\begin{pmatrix} ");
-        code.push_str(codegen_row(&self.children).as_str());
-        code.push_str(" \\end{pmatrix}");
-        code
+        let env = self.variant.as_deref().unwrap_or("pmatrix");
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| codegen_row(row))
+            .collect::<Vec<String>>()
+            .join(" \\\\ ");
+        match self.divider {
+            Some(divider) => {
+                let columns = self.rows.iter().map(|r| r.len()).max().unwrap_or(0);
+                let (open, close) = delimiters(env);
+                format!(
+                    "\\left{} \\begin{{array}}{{{}|{}}} {} \\end{{array}} \\right{}",
+                    open,
+                    "c".repeat(divider),
+                    "c".repeat(columns.saturating_sub(divider)),
+                    rows,
+                    close
+                )
+            }
+            None => format!("\\begin{{{}}} {} \\end{{{}}}", env, rows, env),
+        }
+    }
+
+    fn as_vector(&self) -> Option<&Vector_> {
+        Some(self)
+    }
+}
+
+fn delimiters(env: &str) -> (&'static str, &'static str) {
+    match env {
+        "bmatrix" => ("[", "]"),
+        "Bmatrix" => ("\\{", "\\}"),
+        "vmatrix" => ("|", "|"),
+        "Vmatrix" => ("\\|", "\\|"),
+        _ => ("(", ")"),
+    }
+}
+
+fn codegen_row(row: &[Cell]) -> String {
+    let vertical = row.iter().any(|c| matches!(c, Cell::VerticalDots))
+        && row.iter().all(|c| !matches!(c, Cell::Node(_)));
+    row.iter()
+        .map(|cell| match cell {
+            Cell::Node(node) => node.codegen(),
+            Cell::HorizontalDots if vertical => "\\ddots".to_string(),
+            Cell::HorizontalDots => "\\cdots".to_string(),
+            Cell::VerticalDots => "\\vdots".to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(" & ")
+}
+
+fn is_variant(tokens: &Vec<AtExpToken>, pos: i32) -> bool {
+    matches!(token_from_list(tokens, pos), AtExpToken::Text(t) if MATRIX_VARIANTS.contains(&t.as_str()))
+}
+
+/// A matrix environment name directly followed by `[`, e.g. `bmatrix[1, 2]`.
+pub fn is_variant_opener(tokens: &Vec<AtExpToken>, pos: i32) -> bool {
+    is_variant(tokens, pos) && token_from_list(tokens, pos + 1).is_opertor_or_keyword("[")
+}
+
+/// Turns `;`, `|` and `...` directly inside `[]` into keywords.
+pub fn mark_matrix_keywords(tokens: &mut [AtExpToken]) {
+    let mut brackets: Vec<&str> = Vec::new();
+    for token in tokens.iter_mut() {
+        match token {
+            AtExpToken::OperatorOrKeyword(op) if matches!(op.as_str(), "[" | "(" | "{") => {
+                brackets.push(match op.as_str() {
+                    "[" => "[",
+                    _ => "",
+                });
+            }
+            AtExpToken::OperatorOrKeyword(op) if matches!(op.as_str(), "]" | ")" | "}") => {
+                brackets.pop();
+            }
+            AtExpToken::Text(t) if brackets.last() == Some(&"[") => {
+                if matches!(t.as_str(), ";" | "|" | "...") {
+                    *token = AtExpToken::OperatorOrKeyword(t.clone());
+                }
+            }
+            _ => {}
+        }
     }
 }
 
 pub fn parse(tokens: &Vec<AtExpToken>, start: i32) -> Result<OpAstNode, String> {
-    let mut children: Vec<AtExpToken> = Vec::new();
-    if token_from_list(tokens, start).is_opertor_or_keyword("[") {
-        let mut end = start + 1;
-        if token_from_list(tokens, start + 1).is_opertor_or_keyword("]") {
-            return Ok(Some((Rc::new(Vector_ { children }), 2)));
+    let (variant, open) = if is_variant_opener(tokens, start) {
+        match token_from_list(tokens, start) {
+            AtExpToken::Text(variant) => (Some(variant), start + 1),
+            _ => return Ok(None),
         }
-        let len = tokens.len() as i32;
-        while end < len {
-            if token_from_list(tokens, end).is_ast_node() {
-                children.push(token_from_list(tokens, end));
-                end += 1;
-            } else {
-                return Ok(None);
+    } else if token_from_list(tokens, start).is_opertor_or_keyword("[")
+        && !is_variant(tokens, start - 1)
+    {
+        (None, start)
+    } else {
+        return Ok(None);
+    };
+    let mut rows: Vec<Vec<Cell>> = vec![Vec::new()];
+    let mut divider: Option<usize> = None;
+    let mut end = open + 1;
+    if token_from_list(tokens, end).is_opertor_or_keyword("]") {
+        rows.clear();
+    } else {
+        loop {
+            let row = rows.last_mut().unwrap();
+            match token_from_list(tokens, end) {
+                AtExpToken::AstNode(node) => row.push(Cell::Node(node)),
+                t if t.is_opertor_or_keyword(":") => row.push(Cell::VerticalDots),
+                t if t.is_opertor_or_keyword("...") => row.push(Cell::HorizontalDots),
+                _ => return Ok(None),
             }
-            if token_from_list(tokens, end).is_opertor_or_keyword(",") {
-                end += 1;
-            } else if token_from_list(tokens, end).is_opertor_or_keyword("]") {
-                return Ok(Some((
-                    Rc::new(Vector_ { children }),
-                    (end - start + 1) as usize,
-                )));
+            end += 1;
+            let separator = token_from_list(tokens, end);
+            if separator.is_opertor_or_keyword(",") {
+            } else if separator.is_opertor_or_keyword(";") {
+                rows.push(Vec::new());
+            } else if separator.is_opertor_or_keyword("|") {
+                set_divider(&mut divider, row.len())?;
+            } else if separator.is_opertor_or_keyword("]") {
+                break;
             } else {
                 return Ok(None);
             }
+            end += 1;
         }
-        Ok(None)
-    } else {
-        Ok(None)
     }
+    let vector = stack_rows(Vector_ {
+        rows,
+        divider,
+        variant,
+    })?;
+    Ok(Some((Rc::new(vector), (end - start + 1) as usize)))
 }
 
-fn codegen_row(children: &Vec<AtExpToken>) -> String {
-    let mut code = String::new();
-    let mut first = true;
-    let mut dont_add_ampersand = false;
-    for child in children {
-        if !first {
-            if !dont_add_ampersand {
-                code.push_str(" & ");
-            } else {
-                dont_add_ampersand = false;
-            }
+fn set_divider(divider: &mut Option<usize>, position: usize) -> Result<(), String> {
+    match divider {
+        Some(d) if *d != position => Err(format!(
+            "Augmented matrix has dividers after both column {} and column {}.",
+            d, position
+        )),
+        _ => {
+            *divider = Some(position);
+            Ok(())
         }
-        if let AtExpToken::AstNode(child) = child {
-            let mut child_code = child.codegen();
-            if child_code.starts_with("\\begin{pmatrix}") {
-                if !first {
-                    code.push_str(" \\\\ ");
-                }
-                let a = child_code[16..child_code.len() - 14].to_string();
-                child_code = a.clone();
-                dont_add_ampersand = true;
-            }
-            code.push_str(&child_code);
+    }
+}
+
+/// A vector of vectors, `[[1, 2], [3, 4]]`, is a matrix with the inner vectors as rows.
+fn stack_rows(vector: Vector_) -> Result<Vector_, String> {
+    if vector.rows.len() != 1 || vector.rows[0].is_empty() {
+        return Ok(vector);
+    }
+    let mut inner_rows = Vec::new();
+    for cell in &vector.rows[0] {
+        match cell {
+            Cell::Node(node) => match node.as_vector() {
+                Some(inner) if inner.variant.is_none() => inner_rows.push(inner.clone()),
+                _ => return Ok(vector),
+            },
+            _ => return Ok(vector),
+        }
+    }
+    let mut stacked = Vector_ {
+        rows: Vec::new(),
+        divider: vector.divider,
+        variant: vector.variant,
+    };
+    if stacked.divider.is_some() {
+        return Err("Augmented matrix dividers must be inside the rows.".to_string());
+    }
+    for inner in inner_rows {
+        if let Some(divider) = inner.divider {
+            set_divider(&mut stacked.divider, divider)?;
         }
-        first = false;
+        stacked.rows.extend(inner.rows);
     }
-    code
+    Ok(stacked)
 }
@@ -0,0 +1,321 @@
+/// Commands defined by the LaTeX kernel, the standard classes and `amsmath`.
+/// `\newcommand` fails on these, so declaring them with `@name = ...` is most likely a mistake.
+static LATEX_COMMANDS: [&str; 308] = [
+    // Document structure
+    "documentclass",
+    "usepackage",
+    "begin",
+    "end",
+    "part",
+    "chapter",
+    "section",
+    "subsection",
+    "subsubsection",
+    "paragraph",
+    "subparagraph",
+    "appendix",
+    "tableofcontents",
+    "listoffigures",
+    "listoftables",
+    "maketitle",
+    "title",
+    "author",
+    "date",
+    "thanks",
+    "abstract",
+    "item",
+    "caption",
+    "label",
+    "ref",
+    "pageref",
+    "eqref",
+    "cite",
+    "nocite",
+    "bibliography",
+    "bibliographystyle",
+    "footnote",
+    "footnotemark",
+    "footnotetext",
+    "marginpar",
+    "include",
+    "includeonly",
+    "input",
+    "index",
+    "glossary",
+    "frontmatter",
+    "mainmatter",
+    "backmatter",
+    // Text formatting
+    "textbf",
+    "textit",
+    "textsl",
+    "textsc",
+    "texttt",
+    "textrm",
+    "textsf",
+    "textmd",
+    "textup",
+    "textnormal",
+    "emph",
+    "underline",
+    "em",
+    "bf",
+    "it",
+    "sl",
+    "sc",
+    "tt",
+    "rm",
+    "sf",
+    "bfseries",
+    "itshape",
+    "slshape",
+    "scshape",
+    "ttfamily",
+    "rmfamily",
+    "sffamily",
+    "mdseries",
+    "upshape",
+    "normalfont",
+    "tiny",
+    "scriptsize",
+    "footnotesize",
+    "small",
+    "normalsize",
+    "large",
+    "Large",
+    "LARGE",
+    "huge",
+    "Huge",
+    "text",
+    "mbox",
+    "makebox",
+    "fbox",
+    "framebox",
+    "parbox",
+    "raisebox",
+    "rule",
+    "textcolor",
+    "color",
+    "colorbox",
+    // Spacing and breaks
+    "newline",
+    "linebreak",
+    "nolinebreak",
+    "newpage",
+    "clearpage",
+    "cleardoublepage",
+    "pagebreak",
+    "nopagebreak",
+    "hspace",
+    "vspace",
+    "hfill",
+    "vfill",
+    "hfil",
+    "vfil",
+    "quad",
+    "qquad",
+    "smallskip",
+    "medskip",
+    "bigskip",
+    "indent",
+    "noindent",
+    "centering",
+    "raggedright",
+    "raggedleft",
+    "par",
+    "hline",
+    "cline",
+    "vline",
+    "multicolumn",
+    "and",
+    // Definitions
+    "newcommand",
+    "renewcommand",
+    "providecommand",
+    "newenvironment",
+    "renewenvironment",
+    "newcounter",
+    "setcounter",
+    "addtocounter",
+    "stepcounter",
+    "refstepcounter",
+    "value",
+    "newlength",
+    "setlength",
+    "addtolength",
+    "settowidth",
+    "def",
+    "let",
+    "relax",
+    "the",
+    "arabic",
+    "roman",
+    "Roman",
+    "alph",
+    "Alph",
+    "fnsymbol",
+    "includegraphics",
+    "url",
+    "href",
+    "verb",
+    "today",
+    "LaTeX",
+    "TeX",
+    "ldots",
+    "dots",
+    "cdots",
+    "vdots",
+    "ddots",
+    // Math
+    "frac",
+    "dfrac",
+    "tfrac",
+    "sqrt",
+    "sum",
+    "prod",
+    "int",
+    "iint",
+    "iiint",
+    "oint",
+    "lim",
+    "limsup",
+    "liminf",
+    "sup",
+    "inf",
+    "max",
+    "min",
+    "arg",
+    "det",
+    "dim",
+    "exp",
+    "gcd",
+    "hom",
+    "ker",
+    "lg",
+    "ln",
+    "log",
+    "Pr",
+    "sin",
+    "cos",
+    "tan",
+    "cot",
+    "sec",
+    "csc",
+    "arcsin",
+    "arccos",
+    "arctan",
+    "sinh",
+    "cosh",
+    "tanh",
+    "coth",
+    "deg",
+    "left",
+    "right",
+    "big",
+    "Big",
+    "bigg",
+    "Bigg",
+    "vec",
+    "hat",
+    "bar",
+    "dot",
+    "ddot",
+    "tilde",
+    "check",
+    "breve",
+    "acute",
+    "grave",
+    "overline",
+    "underbrace",
+    "overbrace",
+    "mathrm",
+    "mathbf",
+    "mathit",
+    "mathsf",
+    "mathtt",
+    "mathcal",
+    "mathbb",
+    "mathfrak",
+    "boldsymbol",
+    "operatorname",
+    "binom",
+    "pmod",
+    "bmod",
+    "mod",
+    "cdot",
+    "times",
+    "div",
+    "pm",
+    "mp",
+    "leq",
+    "geq",
+    "neq",
+    "approx",
+    "equiv",
+    "sim",
+    "infty",
+    "partial",
+    "nabla",
+    "forall",
+    "exists",
+    "in",
+    "notin",
+    "subset",
+    "subseteq",
+    "cup",
+    "cap",
+    "to",
+    "mapsto",
+    "implies",
+    "iff",
+    "land",
+    "lor",
+    "lnot",
+    "neg",
+    "angle",
+    "prime",
+    "alpha",
+    "beta",
+    "gamma",
+    "delta",
+    "epsilon",
+    "zeta",
+    "eta",
+    "theta",
+    "iota",
+    "kappa",
+    "lambda",
+    "mu",
+    "nu",
+    "xi",
+    "pi",
+    "rho",
+    "sigma",
+    "tau",
+    "upsilon",
+    "phi",
+    "chi",
+    "psi",
+    "omega",
+    "Gamma",
+    "Delta",
+    "Theta",
+    "Lambda",
+    "Xi",
+    "Pi",
+    "Sigma",
+    "Phi",
+    "Psi",
+    "Omega",
+    "tag",
+    "notag",
+    "nonumber",
+    "intertext",
+    "substack",
+    "stackrel",
+    "overset",
+];
+
+pub fn is_latex_command(name: &str) -> bool {
+    LATEX_COMMANDS.contains(&name)
+}
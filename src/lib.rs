@@ -7,14 +7,21 @@ use version::check_for_new_version;
 pub mod ast;
 pub mod at_expression;
 mod bracket_depth;
+mod builtins;
 mod cli;
 mod codegen;
+pub mod colour;
 pub mod compiler;
+mod data;
 mod document;
 mod feature_matrix;
 pub mod grammar;
+mod latex_commands;
+mod linear_algebra;
+pub mod number_format;
 mod parse;
 mod parser_modules;
+pub mod size;
 mod token;
 mod tokenize;
 pub mod typed_value;
@@ -31,41 +38,74 @@ pub fn run_from_args(args: Vec<String>) {
         }
     };
     for job in jobs {
-        run_job(&job);
+        let mut dependencies = run_job(&job).unwrap_or_default();
 
-        let name = job.input_path.clone();
         if job.watches {
-            let mut pre_hash = utils::hash_file(&job.input_path);
-            let mut watcher = notify::recommended_watcher(move |_| {
-                let hash = utils::hash_file(&job.input_path);
-                if hash == "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855" {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            let mut watcher = notify::recommended_watcher(sender).unwrap();
+            watcher
+                .watch(Path::new(&job.input_path), RecursiveMode::Recursive)
+                .unwrap();
+            let mut watched: Vec<String> = Vec::new();
+            watch_dependencies(&mut watcher, &mut watched, &dependencies);
+            let mut pre_hash = hash_job_files(&job.input_path, &dependencies);
+            for _ in receiver {
+                if utils::hash_file(&job.input_path)
+                    == "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+                {
                     // Empty file
-                    return;
+                    continue;
                 }
+                let hash = hash_job_files(&job.input_path, &dependencies);
                 if pre_hash != hash {
-                    run_job(&job);
+                    if let Some(new_dependencies) = run_job(&job) {
+                        dependencies = new_dependencies;
+                        watch_dependencies(&mut watcher, &mut watched, &dependencies);
+                    }
                 }
-                pre_hash = hash;
-            })
-            .unwrap();
-            watcher
-                .watch(Path::new(&name), RecursiveMode::Recursive)
-                .unwrap();
-            loop {
-                std::thread::sleep(std::time::Duration::from_millis(1000));
+                pre_hash = hash_job_files(&job.input_path, &dependencies);
             }
         }
     }
 }
 
-fn run_job(job: &Job) {
+/// Data files loaded by the document are watched too, including ones added since the last build.
+fn watch_dependencies(
+    watcher: &mut impl Watcher,
+    watched: &mut Vec<String>,
+    dependencies: &[String],
+) {
+    for dependency in dependencies {
+        if !watched.contains(dependency)
+            && watcher
+                .watch(Path::new(dependency), RecursiveMode::NonRecursive)
+                .is_ok()
+        {
+            watched.push(dependency.clone());
+        }
+    }
+}
+
+fn hash_job_files(input_path: &String, dependencies: &[String]) -> String {
+    let mut hash = utils::hash_file(input_path);
+    for dependency in dependencies {
+        if Path::new(dependency).exists() {
+            hash.push_str(&utils::hash_file(dependency));
+        }
+    }
+    hash
+}
+
+/// Returns the files the document depends on if it compiled.
+fn run_job(job: &Job) -> Option<Vec<String>> {
     match compile(job.clone()) {
-        Ok(_) => {
+        Ok(dependencies) => {
             println!(
                 "[{}] Ouput \"{}\".",
                 "Success".green(),
                 job.output_path.clone()
             );
+            Some(dependencies)
         }
         Err(e) => {
             println!(
@@ -74,6 +114,7 @@ fn run_job(job: &Job) {
                 job.input_path.clone(),
                 e
             );
+            None
         }
-    };
+    }
 }
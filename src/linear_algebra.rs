@@ -0,0 +1,208 @@
+use crate::typed_value::TypedValue;
+
+/// Describes the shape of a value for dimension mismatch errors.
+fn describe(value: &TypedValue) -> String {
+    match value {
+        TypedValue::Vector(v) => format!("vector of length {}", v.len()),
+        TypedValue::Matrix(m) => format!("{}x{} matrix", m.len(), columns(m)),
+        _ => value.type_name(),
+    }
+}
+
+fn columns(m: &[Vec<f64>]) -> usize {
+    m.first().map(|r| r.len()).unwrap_or(0)
+}
+
+fn mismatch(operation: &str, lhs: &TypedValue, rhs: &TypedValue) -> String {
+    format!(
+        "Tried to {} a {} and a {} in @() expression.",
+        operation,
+        describe(lhs),
+        describe(rhs)
+    )
+}
+
+/// Builds a vector from one row or a matrix from several rows of the same length.
+pub fn from_rows(rows: Vec<Vec<f64>>) -> Result<TypedValue, String> {
+    if rows.len() == 1 {
+        return Ok(TypedValue::Vector(rows.into_iter().next().unwrap()));
+    }
+    if rows.iter().any(|r| r.len() != columns(&rows)) {
+        return Err("Rows of matrix in @() expression have different lengths.".to_string());
+    }
+    Ok(TypedValue::Matrix(rows))
+}
+
+/// Element-wise addition, or subtraction with `sign` of `-1`.
+pub fn add(lhs: &TypedValue, rhs: &TypedValue, sign: f64) -> Result<TypedValue, String> {
+    let operation = if sign < 0.0 { "subtract" } else { "add" };
+    let zip = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(a, b)| a + sign * b).collect();
+    match (lhs, rhs) {
+        (TypedValue::Vector(a), TypedValue::Vector(b)) if a.len() == b.len() => {
+            Ok(TypedValue::Vector(zip(a, b)))
+        }
+        (TypedValue::Matrix(a), TypedValue::Matrix(b))
+            if a.len() == b.len() && columns(a) == columns(b) =>
+        {
+            Ok(TypedValue::Matrix(
+                a.iter().zip(b).map(|(a, b)| zip(a, b)).collect(),
+            ))
+        }
+        _ => Err(mismatch(operation, lhs, rhs)),
+    }
+}
+
+pub fn scale(value: &TypedValue, factor: f64) -> TypedValue {
+    let scale_row = |r: &Vec<f64>| r.iter().map(|x| x * factor).collect();
+    match value {
+        TypedValue::Vector(v) => TypedValue::Vector(scale_row(v)),
+        TypedValue::Matrix(m) => TypedValue::Matrix(m.iter().map(scale_row).collect()),
+        TypedValue::Number(n) => TypedValue::Number(n * factor),
+        _ => value.clone(),
+    }
+}
+
+pub fn divide(value: &TypedValue, divisor: f64) -> Result<TypedValue, String> {
+    if divisor == 0.0 {
+        return Err(format!(
+            "Can't divide a {} by zero in @() expression.",
+            describe(value)
+        ));
+    }
+    Ok(scale(value, 1.0 / divisor))
+}
+
+/// Scalar and matrix multiplication. Vectors are columns on the right and rows on the left, so
+/// a matrix times a vector is a column.
+pub fn multiply(lhs: &TypedValue, rhs: &TypedValue) -> Result<TypedValue, String> {
+    match (lhs, rhs) {
+        (TypedValue::Number(n), TypedValue::Vector(_) | TypedValue::Matrix(_)) => {
+            Ok(scale(rhs, *n))
+        }
+        (TypedValue::Vector(_) | TypedValue::Matrix(_), TypedValue::Number(n)) => {
+            Ok(scale(lhs, *n))
+        }
+        (TypedValue::Matrix(a), TypedValue::Matrix(b)) if columns(a) == b.len() => {
+            Ok(TypedValue::Matrix(
+                a.iter()
+                    .map(|row| {
+                        (0..columns(b))
+                            .map(|j| row.iter().zip(b).map(|(x, r)| x * r[j]).sum())
+                            .collect()
+                    })
+                    .collect(),
+            ))
+        }
+        (TypedValue::Matrix(a), TypedValue::Vector(v)) if columns(a) == v.len() => {
+            Ok(TypedValue::Matrix(
+                a.iter()
+                    .map(|row| vec![row.iter().zip(v).map(|(x, y)| x * y).sum()])
+                    .collect(),
+            ))
+        }
+        (TypedValue::Vector(v), TypedValue::Matrix(b)) if v.len() == b.len() => {
+            Ok(TypedValue::Vector(
+                (0..columns(b))
+                    .map(|j| v.iter().zip(b).map(|(x, r)| x * r[j]).sum())
+                    .collect(),
+            ))
+        }
+        (TypedValue::Vector(_), TypedValue::Vector(_)) => Err(format!(
+            "{} Use dot() or cross() to multiply vectors.",
+            mismatch("multiply", lhs, rhs)
+        )),
+        _ => Err(mismatch("multiply", lhs, rhs)),
+    }
+}
+
+pub fn transpose(value: &TypedValue) -> Result<TypedValue, String> {
+    match value {
+        TypedValue::Vector(v) => Ok(TypedValue::Matrix(v.iter().map(|x| vec![*x]).collect())),
+        TypedValue::Matrix(m) => {
+            let rows = (0..columns(m))
+                .map(|j| m.iter().map(|r| r[j]).collect())
+                .collect();
+            Ok(TypedValue::Matrix(rows))
+        }
+        _ => Err(format!(
+            "Can't transpose a {} in @() expression.",
+            describe(value)
+        )),
+    }
+}
+
+pub fn determinant(value: &TypedValue) -> Result<f64, String> {
+    let mut m = match value {
+        TypedValue::Matrix(m) if m.len() == columns(m) => m.clone(),
+        _ => {
+            return Err(format!(
+                "Can only take the determinant of a square matrix, found a {} in @() expression.",
+                describe(value)
+            ))
+        }
+    };
+    // Gaussian elimination with partial pivoting.
+    let n = m.len();
+    let mut det = 1.0;
+    for i in 0..n {
+        let pivot = (i..n)
+            .max_by(|a, b| m[*a][i].abs().total_cmp(&m[*b][i].abs()))
+            .unwrap();
+        if m[pivot][i] == 0.0 {
+            return Ok(0.0);
+        }
+        if pivot != i {
+            m.swap(pivot, i);
+            det = -det;
+        }
+        det *= m[i][i];
+        let pivot_row = m[i].clone();
+        for row in m.iter_mut().skip(i + 1) {
+            let factor = row[i] / pivot_row[i];
+            for (x, p) in row.iter_mut().zip(&pivot_row).skip(i) {
+                *x -= factor * p;
+            }
+        }
+    }
+    Ok(det)
+}
+
+pub fn dot(lhs: &TypedValue, rhs: &TypedValue) -> Result<f64, String> {
+    match (lhs, rhs) {
+        (TypedValue::Vector(a), TypedValue::Vector(b)) if a.len() == b.len() => {
+            Ok(a.iter().zip(b).map(|(a, b)| a * b).sum())
+        }
+        _ => Err(mismatch("take the dot product of", lhs, rhs)),
+    }
+}
+
+pub fn cross(lhs: &TypedValue, rhs: &TypedValue) -> Result<TypedValue, String> {
+    match (lhs, rhs) {
+        (TypedValue::Vector(a), TypedValue::Vector(b)) if a.len() == 3 && b.len() == 3 => {
+            Ok(TypedValue::Vector(vec![
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ]))
+        }
+        _ => Err(format!(
+            "{} The cross product needs two vectors of length 3.",
+            mismatch("take the cross product of", lhs, rhs)
+        )),
+    }
+}
+
+/// Vectors print as a row, matrices row by row, both in a `pmatrix`.
+pub fn codegen(rows: &[Vec<f64>], format: &dyn Fn(f64) -> String) -> String {
+    let rows = rows
+        .iter()
+        .map(|r| {
+            r.iter()
+                .map(|x| format(*x))
+                .collect::<Vec<String>>()
+                .join(" & ")
+        })
+        .collect::<Vec<String>>()
+        .join(" \\\\ ");
+    format!("\\begin{{pmatrix}} {} \\end{{pmatrix}}", rows)
+}
@@ -0,0 +1,187 @@
+use crate::linear_algebra;
+use crate::typed_value::TypedValue;
+
+/// Digits beyond this are noise for an `f64`.
+const MAX_PRECISION: usize = 17;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Style {
+    /// `.2f`, a fixed number of decimals.
+    Fixed,
+    /// `.3s`, significant figures.
+    Significant,
+    /// `.2e`, `1.23 \times 10^{4}`.
+    Scientific,
+    /// `.3n`, `siunitx` `\num{}`.
+    Siunitx,
+}
+
+/// How numbers computed by @() expressions are printed, e.g. `,.2f`.
+#[derive(Clone)]
+pub struct NumberFormat {
+    style: Style,
+    precision: Option<usize>,
+    thousands_separator: bool,
+}
+
+impl NumberFormat {
+    /// Parses a format specifier `[,][.precision](f|s|e|n)`.
+    pub fn parse(spec: &str) -> Result<NumberFormat, String> {
+        let invalid = || {
+            format!(
+                "Invalid number format \"{}\", expected something like \".3f\", \".3s\", \".3e\" or \".3n\".",
+                spec
+            )
+        };
+        let mut rest = spec.trim();
+        let thousands_separator = rest.starts_with(',');
+        if thousands_separator {
+            rest = &rest[1..];
+        }
+        let style = match rest.chars().last() {
+            Some('f') => Style::Fixed,
+            Some('s') => Style::Significant,
+            Some('e') => Style::Scientific,
+            Some('n') => Style::Siunitx,
+            _ => return Err(invalid()),
+        };
+        rest = &rest[..rest.len() - 1];
+        let precision = match rest.strip_prefix('.') {
+            Some(digits) => Some(digits.parse::<usize>().map_err(|_| invalid())?),
+            None if rest.is_empty() => None,
+            None => return Err(invalid()),
+        };
+        if precision.is_some_and(|p| p > MAX_PRECISION) {
+            return Err(format!(
+                "Invalid number format \"{}\", the precision can be at most {}.",
+                spec, MAX_PRECISION
+            ));
+        }
+        if style == Style::Significant && precision == Some(0) {
+            return Err(format!(
+                "Invalid number format \"{}\", numbers need at least 1 significant figure.",
+                spec
+            ));
+        }
+        Ok(NumberFormat {
+            style,
+            precision,
+            thousands_separator,
+        })
+    }
+
+    /// Whether the output needs `siunitx`.
+    pub fn is_siunitx(&self) -> bool {
+        self.style == Style::Siunitx
+    }
+
+    pub fn format(&self, n: f64) -> String {
+        let formatted = match self.style {
+            Style::Fixed => format!("{:.*}", self.precision.unwrap_or(2), n),
+            Style::Significant => significant(n, self.precision.unwrap_or(3)),
+            Style::Scientific => return self.scientific(n),
+            Style::Siunitx => {
+                let digits = match self.precision {
+                    Some(p) if p > 0 => significant(n, p),
+                    _ => n.to_string(),
+                };
+                return format!("\\num{{{}}}", digits);
+            }
+        };
+        if self.thousands_separator {
+            separate_thousands(&formatted)
+        } else {
+            formatted
+        }
+    }
+
+    fn scientific(&self, n: f64) -> String {
+        let precision = self.precision.unwrap_or(2);
+        let (mut mantissa, mut exponent) = (n, 0);
+        if n != 0.0 {
+            exponent = n.abs().log10().floor() as i32;
+            mantissa = n / 10f64.powi(exponent);
+            // Rounding can carry into the next power of 10, e.g. 9.996 to 10.00.
+            if format!("{:.*}", precision, mantissa.abs()).starts_with("10") {
+                exponent += 1;
+                mantissa /= 10.0;
+            }
+        }
+        let mantissa = format!("{:.*}", precision, mantissa);
+        if exponent == 0 {
+            mantissa
+        } else {
+            format!("{} \\times 10^{{{}}}", mantissa, exponent)
+        }
+    }
+}
+
+fn significant(n: f64, figures: usize) -> String {
+    if n == 0.0 {
+        return format!("{:.*}", figures - 1, 0.0);
+    }
+    let magnitude = n.abs().log10().floor() as i32;
+    let decimals = figures as i32 - 1 - magnitude;
+    if decimals >= 0 {
+        format!("{:.*}", decimals as usize, n)
+    } else {
+        let scale = 10f64.powi(-decimals);
+        format!("{}", (n / scale).round() * scale)
+    }
+}
+
+/// Groups the digits before the decimal point, `12{,}345.6`.
+fn separate_thousands(number: &str) -> String {
+    let (sign, digits) = match number.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", number),
+    };
+    let (integer, fraction) = match digits.find('.') {
+        Some(point) => digits.split_at(point),
+        None => (digits, ""),
+    };
+    let mut grouped = String::new();
+    for (i, c) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push_str("{,}");
+        }
+        grouped.push(c);
+    }
+    format!("{}{}{}", sign, grouped, fraction)
+}
+
+/// Prints a value with `format`, or as is without one. TeX can't print `inf` or `NaN`.
+pub fn format_value(value: &TypedValue, format: Option<&NumberFormat>) -> Result<String, String> {
+    let finite = match value {
+        TypedValue::Number(n) => n.is_finite(),
+        TypedValue::Vector(v) => v.iter().all(|x| x.is_finite()),
+        TypedValue::Matrix(m) => m.iter().flatten().all(|x| x.is_finite()),
+        _ => true,
+    };
+    if !finite {
+        return Err(format!(
+            "The result {} of @() expression is not a finite number.",
+            value.stringify()
+        ));
+    }
+    let format = match format {
+        Some(format) => format,
+        None => return Ok(value.stringify()),
+    };
+    Ok(match value {
+        TypedValue::Number(n) => format.format(*n),
+        TypedValue::Vector(v) => {
+            linear_algebra::codegen(std::slice::from_ref(v), &|x| format.format(x))
+        }
+        TypedValue::Matrix(m) => linear_algebra::codegen(m, &|x| format.format(x)),
+        // Only plain digits are valid in a dimension.
+        TypedValue::Size(s) if matches!(format.style, Style::Fixed | Style::Significant) => {
+            let format = NumberFormat {
+                thousands_separator: false,
+                ..format.clone()
+            };
+            format!("{}{}", format.format(s.value), s.unit)
+        }
+        _ => value.stringify(),
+    })
+}
@@ -1,24 +1,43 @@
 use std::rc::Rc;
 
+use crate::at_expression::string_to_typed_value;
 use crate::bracket_depth::BrackDepths;
+use crate::cli::print_warning;
+use crate::colour::Colour;
 use crate::compiler::Job;
 use crate::document::*;
 use crate::feature_matrix::get_status_list;
 use crate::feature_matrix::FeatureStatusList;
+use crate::grammar::symbols::text_to_ascii;
+use crate::number_format::NumberFormat;
+use crate::parser_modules::aligned_equation::LiaAlignedEquation;
 use crate::parser_modules::bold_italic::BoldItalic;
 use crate::parser_modules::comments::Comment;
+use crate::parser_modules::conditionals::LiaIf;
+use crate::parser_modules::description_list::LiaMarkdownDescriptionListParser;
 use crate::parser_modules::enumerated_list::LiaMardownEnumListParser;
 use crate::parser_modules::environments::LiaEnvParser;
 use crate::parser_modules::equation::LiaEquation;
+use crate::parser_modules::footnotes::{
+    resolve_footnotes, FootnoteDefinition, FootnoteParser, PendingFootnote,
+};
+use crate::parser_modules::horizontal_rule::LiaMarkdownHorizontalRule;
 use crate::parser_modules::imports::LiaUseParser;
 use crate::parser_modules::inline_code::InlineCode;
+use crate::parser_modules::inline_math::LiaInlineMath;
+use crate::parser_modules::labels::{
+    resolve_label_references, Label, LabelReferenceParser, PendingReference,
+};
 use crate::parser_modules::list::LiaMardownListParser;
+use crate::parser_modules::loops::LiaForLoop;
+use crate::parser_modules::quote::LiaMarkdownQuoteParser;
 use crate::parser_modules::section::LiaMarkDownSections;
 use crate::parser_modules::tex_command::TexCommandParser;
-use crate::parser_modules::variables::Function;
 use crate::parser_modules::variables::LiaVariableParser;
+use crate::parser_modules::variables::{check_function_calls, Function};
 use crate::token::*;
 use crate::tokenize::*;
+use crate::typed_value::TypedValue;
 use crate::utils::{count_indentation, delta_bracket_depth};
 
 #[derive(Default)]
@@ -26,21 +45,181 @@ pub struct CompilerGlobals {
     imps: NodeList,
     decs: NodeList,
     pub fucntions: Vec<Function>,
+    /// Names declared with `@name = ...` and where, to catch redefinitions.
+    pub declarations: Vec<(String, Location)>,
+    /// Calls of `@name(...)`, checked against the declarations once the document is parsed.
+    pub function_calls: Vec<(String, Location)>,
+    pub labels: Vec<Label>,
+    pub label_references: Vec<PendingReference>,
+    pub footnotes: Vec<Rc<Footnote>>,
+    pub footnote_definitions: Vec<FootnoteDefinition>,
+    pub footnote_references: Vec<PendingFootnote>,
+    pub required_packages: Vec<String>,
+    /// Replace Unicode in plain text with pdflatex safe TeX, set with `@LIAASCII`.
+    pub ascii_text: bool,
+    /// Expand every function at compile time, set with `@LIAINLINE`.
+    pub inline_functions: bool,
+    /// How many inline function calls are being expanded inside each other.
+    pub inline_depth: usize,
+    /// Default format of numbers computed in @() expressions, set with `@LIANUMBERFORMAT`.
+    pub number_format: Option<NumberFormat>,
+    /// Colours declared with `@name = #RRGGBB`, usable by name in @() expressions.
+    pub colours: Vec<Colour>,
+    /// Constants declared with `@name = value`, usable by name in @() expressions and as `@name` in equations.
+    pub constants: Vec<(String, TypedValue)>,
+    /// Set while math is parsed as text, where `^[` is a superscript and not a footnote.
+    pub in_math: bool,
+    /// Iterations of the outermost `for` loop being expanded and the loops nested in it, `None`
+    /// outside of loops.
+    pub loop_iterations: Option<usize>,
+    /// Data files loaded with `load()`.
+    pub dependencies: Vec<String>,
     pub feature_status_list: FeatureStatusList,
     pub job: Job,
 }
 
+/// See `CompilerGlobals::checkpoint`.
+pub struct Checkpoint {
+    imps: usize,
+    decs: usize,
+    fucntions: usize,
+    declarations: usize,
+    function_calls: usize,
+    labels: usize,
+    label_references: usize,
+    footnotes: usize,
+    footnote_definitions: usize,
+    footnote_references: usize,
+    required_packages: usize,
+    colours: usize,
+    constants: usize,
+    ascii_text: bool,
+    inline_functions: bool,
+    number_format: Option<NumberFormat>,
+    dependencies: usize,
+    feature_status_list: FeatureStatusList,
+}
+
+impl CompilerGlobals {
+    pub fn require_package(&mut self, package: &str) {
+        if !self.required_packages.iter().any(|p| p == package) {
+            self.required_packages.push(package.to_string());
+        }
+    }
+
+    /// Prints a warning located like errors are, `"file" line:column`.
+    pub fn warn(&self, location: Location, message: String) {
+        print_warning(format!(
+            "\"{}\" {} {}",
+            self.job.input_path,
+            location.stringify(),
+            message
+        ));
+    }
+
+    /// Remembers what has been declared so far, to undo the declarations of a branch that is not used.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            imps: self.imps.len(),
+            decs: self.decs.len(),
+            fucntions: self.fucntions.len(),
+            declarations: self.declarations.len(),
+            function_calls: self.function_calls.len(),
+            labels: self.labels.len(),
+            label_references: self.label_references.len(),
+            footnotes: self.footnotes.len(),
+            footnote_definitions: self.footnote_definitions.len(),
+            footnote_references: self.footnote_references.len(),
+            required_packages: self.required_packages.len(),
+            colours: self.colours.len(),
+            constants: self.constants.len(),
+            ascii_text: self.ascii_text,
+            inline_functions: self.inline_functions,
+            number_format: self.number_format.clone(),
+            dependencies: self.dependencies.len(),
+            feature_status_list: self.feature_status_list.clone(),
+        }
+    }
+
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        self.imps.truncate(checkpoint.imps);
+        self.decs.truncate(checkpoint.decs);
+        self.fucntions.truncate(checkpoint.fucntions);
+        self.declarations.truncate(checkpoint.declarations);
+        self.function_calls.truncate(checkpoint.function_calls);
+        self.labels.truncate(checkpoint.labels);
+        self.label_references.truncate(checkpoint.label_references);
+        self.footnotes.truncate(checkpoint.footnotes);
+        self.footnote_definitions
+            .truncate(checkpoint.footnote_definitions);
+        self.footnote_references
+            .truncate(checkpoint.footnote_references);
+        self.required_packages
+            .truncate(checkpoint.required_packages);
+        self.colours.truncate(checkpoint.colours);
+        self.constants.truncate(checkpoint.constants);
+        self.ascii_text = checkpoint.ascii_text;
+        self.inline_functions = checkpoint.inline_functions;
+        self.number_format = checkpoint.number_format;
+        self.dependencies.truncate(checkpoint.dependencies);
+        self.feature_status_list = checkpoint.feature_status_list;
+    }
+
+    pub fn constant(&self, name: &str) -> Option<&TypedValue> {
+        self.constants
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value)
+    }
+
+    fn import_required_packages(&mut self) {
+        let imported = self
+            .imps
+            .iter()
+            .map(|node| node.codegen())
+            .collect::<String>();
+        for package in &self.required_packages {
+            if imported.contains(&format!("{{{package}}}")) {
+                continue;
+            }
+            self.imps.push(Rc::new(TexCommand {
+                command: "usepackage".to_string(),
+                args: vec![Arg {
+                    arg: vec![Rc::new(Text {
+                        text: package.clone(),
+                    })],
+                    arg_type: ArgType::Curly,
+                }],
+            }));
+            self.imps.push(Rc::new(Text {
+                text: "\n".to_string(),
+            }));
+        }
+    }
+}
+
 pub fn parse(tokens: TokenList, job: Job) -> Result<Doc, String> {
     let len = tokens.len();
     let mut other_doc_locations = CompilerGlobals::default();
     other_doc_locations.job = job;
     other_doc_locations.feature_status_list = get_status_list(env!("CARGO_PKG_VERSION"))?;
+    for (name, value) in &other_doc_locations.job.defines {
+        let value = string_to_typed_value(value.clone())?;
+        other_doc_locations.constants.push((name.clone(), value));
+    }
 
     let doc = node_list(tokens, 0, len, &mut other_doc_locations)?;
+    resolve_label_references(&mut other_doc_locations)?;
+    resolve_footnotes(&mut other_doc_locations)?;
+    check_function_calls(&other_doc_locations);
+    other_doc_locations.import_required_packages();
     let doc = Doc {
         imports: other_doc_locations.imps,
         declarations: other_doc_locations.decs,
         document: doc,
+        footnotes: other_doc_locations.footnotes,
+        dependencies: other_doc_locations.dependencies,
     };
     Ok(doc)
 }
@@ -54,8 +233,11 @@ pub fn node_list(
     // TODO: Refactor this function to be more readable.
     //       It's impossible to work with at the moment.
 
-    let mut node_parsers: [Box<dyn NodeParser>; 11] = [
+    let mut node_parsers: [Box<dyn NodeParser>; 20] = [
         Box::new(LiaMarkDownSections::default()),
+        Box::new(LiaMarkdownDescriptionListParser::default()),
+        Box::new(LiaMarkdownQuoteParser::default()),
+        Box::new(LiaMarkdownHorizontalRule::default()),
         Box::new(TexCommandParser::default()),
         Box::new(LiaEnvParser::default()),
         Box::new(LiaUseParser::default()),
@@ -66,6 +248,12 @@ pub fn node_list(
         Box::new(LiaMardownEnumListParser::default()),
         Box::new(InlineCode::default()),
         Box::new(Comment::default()),
+        Box::new(LabelReferenceParser::default()),
+        Box::new(LiaAlignedEquation::default()),
+        Box::new(FootnoteParser::default()),
+        Box::new(LiaInlineMath::default()),
+        Box::new(LiaForLoop::default()),
+        Box::new(LiaIf::default()),
     ];
 
     let mut items: NodeList = Vec::new();
@@ -75,6 +263,9 @@ pub fn node_list(
     let mut range_started = 0;
     let mut indentation_type: Option<IndentationType> = None;
     let mut bracket_depths_at_start_of_module = BrackDepths::default();
+    // The opening token is revisited once the module is entered, so its
+    // brackets must only be counted the first time round.
+    let mut opener_position: Option<usize> = None;
 
     let mut i = start;
 
@@ -87,7 +278,9 @@ pub fn node_list(
     }
 
     'outer: while i < end {
-        bracket_depths += delta_bracket_depth(&tokens[i]);
+        if opener_position != Some(i) {
+            bracket_depths += delta_bracket_depth(&tokens[i]);
+        }
 
         count_indentation(&tokens, i, &mut indentation, &mut indentation_type);
 
@@ -119,12 +312,18 @@ pub fn node_list(
                 if (node_parsers[j]).is_opener(tokens, i, indentation as i32, other_doc_locations) {
                     in_parser_module = Some(j);
                     range_started = i;
+                    opener_position = Some(i);
                     bracket_depths_at_start_of_module = bracket_depths;
                     continue 'outer;
                 }
             }
 
-            items.push(text_node(&[tokens[i].clone()])?);
+            match &tokens[i] {
+                Token::Misc(text, loc) if other_doc_locations.ascii_text => {
+                    items.push(text_node(&[Token::Misc(text_to_ascii(text), *loc)])?)
+                }
+                token => items.push(text_node(std::slice::from_ref(token))?),
+            }
         }
 
         i += 1;
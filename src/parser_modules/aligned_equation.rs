@@ -0,0 +1,288 @@
+use std::rc::Rc;
+
+use crate::bracket_depth::BrackDepths;
+use crate::document::{DocSection, LabelKind, TexEnvironment, Text};
+use crate::parse::{CompilerGlobals, IndentationType, NodeParser, ParseResult};
+use crate::parser_modules::equation::{codegen_equation, contains_anything_meaningful};
+use crate::parser_modules::inline_math::require_unicode_math_packages;
+use crate::parser_modules::labels::{parse_label_name, register_label};
+use crate::token::*;
+use crate::tokenize::TokenList;
+use crate::utils::{
+    delta_bracket_depth, format_error_string, is_start_of_line, move_past_whitespace,
+};
+
+#[derive(Default)]
+pub struct LiaAlignedEquation {
+    curly_depth: i32,
+}
+
+enum RowNumbering {
+    Numbered,
+    Label(String),
+    NoNumber,
+}
+
+#[allow(unused)]
+impl NodeParser for LiaAlignedEquation {
+    fn is_opener(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        identation: i32,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> bool {
+        let token = &tokens[cursor];
+
+        self.curly_depth = -1;
+        // Documents for versions without the equation syntax keep `align {` as text, as does prose.
+        if !is_start_of_line(tokens, cursor)
+            || !other_doc_locations
+                .feature_status_list
+                .equation_statement_internal_syntax
+                .is_supported()
+        {
+            return false;
+        }
+        match token {
+            Token::Misc(k, _) => {
+                (k == "eqs" || k == "align")
+                    && match move_past_whitespace(tokens, cursor + 1) {
+                        Some(Token::Misc(next, _)) => next == "{" || next == "*",
+                        _ => false,
+                    }
+            }
+            _ => false,
+        }
+    }
+
+    fn is_closer(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        bracket_depths: &BrackDepths,
+        start_bracket_depths: &BrackDepths,
+    ) -> bool {
+        let token = &tokens[cursor];
+
+        if self.curly_depth == -1 {
+            self.curly_depth = bracket_depths.curly;
+        }
+        match token {
+            Token::Misc(t, _) => t == "}" && bracket_depths.curly == self.curly_depth,
+            _ => false,
+        }
+    }
+
+    fn parse(
+        &mut self,
+        tokens: &[Token],
+        range_start: usize,
+        range_end: usize,
+        indentation_type: Option<IndentationType>,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> ParseResult {
+        let tokens = &tokens[range_start..=range_end];
+        require_unicode_math_packages(tokens, other_doc_locations);
+
+        let mut asterisk = false;
+        let mut open_pos = 1;
+        let len = tokens.len();
+        while open_pos < len {
+            match &tokens[open_pos] {
+                Token::Whitespace(_) => open_pos += 1,
+                Token::Misc(t, _) if t == "*" => {
+                    asterisk = true;
+                    open_pos += 1;
+                }
+                Token::Misc(t, _) if t == "{" => break,
+                t => {
+                    return format_error_string(
+                        format! {"Unexpected token \"{}\" in aligned equation statement.", t.stringify()},
+                        t.get_location(),
+                    );
+                }
+            }
+        }
+
+        let mut rows: Vec<String> = Vec::new();
+        for (start, end) in split_rows(tokens, open_pos + 1, len - 1) {
+            let location = tokens[start].get_location();
+            let (end, numbering) = row_numbering(tokens, start, end)?;
+            let mut row = codegen_row(tokens, start, end, location, other_doc_locations)?;
+            match numbering {
+                RowNumbering::Numbered => {}
+                RowNumbering::Label(label) => {
+                    if asterisk {
+                        return format_error_string(
+                            format! {"Rows of anonymous aligned equations can not be labelled, found label \"{label}\"."},
+                            location,
+                        );
+                    }
+                    register_label(
+                        label.clone(),
+                        LabelKind::Equation,
+                        location,
+                        other_doc_locations,
+                    )?;
+                    row = format!("{row} \\label{{{label}}}");
+                }
+                RowNumbering::NoNumber => {
+                    if !asterisk {
+                        row = format!("{row} \\nonumber");
+                    }
+                }
+            }
+            rows.push(row);
+        }
+
+        other_doc_locations.require_package("amsmath");
+
+        Ok((
+            vec![Rc::new(TexEnvironment {
+                name: if asterisk {
+                    "align*".to_string()
+                } else {
+                    "align".to_string()
+                },
+                args: vec![],
+                children: vec![Rc::new(Text {
+                    text: rows.join(" \\\\\n"),
+                })],
+            })],
+            DocSection::Document,
+        ))
+    }
+}
+
+/// Splits the contents of the block into rows on unbracketed newlines. Empty
+/// rows are skipped.
+fn split_rows(tokens: TokenList, start: usize, end: usize) -> Vec<(usize, usize)> {
+    let mut rows = Vec::new();
+    let mut brack_depth = BrackDepths::default();
+    let mut row_start = start;
+    for (i, token) in tokens.iter().enumerate().take(end).skip(start) {
+        brack_depth += delta_bracket_depth(token);
+        if token.is_newline() && brack_depth.is_zero() {
+            push_row(tokens, row_start, i, &mut rows);
+            row_start = i + 1;
+        }
+    }
+    push_row(tokens, row_start, end, &mut rows);
+    rows
+}
+
+fn push_row(tokens: TokenList, start: usize, end: usize, rows: &mut Vec<(usize, usize)>) {
+    let mut start = start;
+    while start < end {
+        match &tokens[start] {
+            Token::Whitespace(_) | Token::Newline => start += 1,
+            _ => break,
+        }
+    }
+    if start < end {
+        rows.push((start, end));
+    }
+}
+
+/// Strips a trailing `#label` or `#*` from a row.
+fn row_numbering(
+    tokens: TokenList,
+    start: usize,
+    end: usize,
+) -> Result<(usize, RowNumbering), String> {
+    let mut brack_depth = BrackDepths::default();
+    let mut hash_pos: Option<usize> = None;
+    for (i, token) in tokens.iter().enumerate().take(end).skip(start) {
+        brack_depth += delta_bracket_depth(token);
+        // A `#` starting the line is tokenized as Markdown.
+        if let Token::Misc(t, _) | Token::LiaMarkDown(t, _) = token {
+            if t == "#" && brack_depth.is_zero() {
+                hash_pos = Some(i);
+            }
+        }
+    }
+    let hash_pos = match hash_pos {
+        Some(pos) => pos,
+        None => return Ok((end, RowNumbering::Numbered)),
+    };
+    if !contains_anything_meaningful(&tokens, start, hash_pos) {
+        return Err(format!(
+            "{} Empty row in aligned equation statement.",
+            tokens[hash_pos].get_location().stringify()
+        ));
+    }
+    let mut label_end = end;
+    while label_end > hash_pos + 1 {
+        if let Token::Whitespace(_) = tokens[label_end - 1] {
+            label_end -= 1;
+        } else {
+            break;
+        }
+    }
+    let location = tokens[hash_pos].get_location();
+    let label_tokens = &tokens[hash_pos + 1..label_end];
+    if label_tokens.len() == 1 && label_tokens[0].stringify() == "*" {
+        return Ok((hash_pos, RowNumbering::NoNumber));
+    }
+    Ok((
+        hash_pos,
+        RowNumbering::Label(parse_label_name(label_tokens, location)?),
+    ))
+}
+
+/// Generates a row, aligning on explicit `&`s or otherwise on the first `=`.
+fn codegen_row(
+    tokens: TokenList,
+    start: usize,
+    end: usize,
+    location: Location,
+    other_doc_locations: &CompilerGlobals,
+) -> Result<String, String> {
+    let mut anchors: Vec<usize> = Vec::new();
+    let mut first_equals: Option<usize> = None;
+    let mut brack_depth = BrackDepths::default();
+    for (i, token) in tokens.iter().enumerate().take(end).skip(start) {
+        brack_depth += delta_bracket_depth(token);
+        if let Token::Misc(t, _) = token {
+            if !brack_depth.is_zero() {
+                continue;
+            }
+            if t == "&" {
+                anchors.push(i);
+            } else if t == "=" && first_equals.is_none() {
+                first_equals = Some(i);
+            }
+        }
+    }
+
+    if anchors.is_empty() {
+        return match first_equals {
+            Some(equals) => Ok(format!(
+                "{} &= {}",
+                codegen_equation(tokens, start, equals, location, other_doc_locations)?,
+                codegen_equation(tokens, equals + 1, end, location, other_doc_locations)?
+            )
+            .trim()
+            .to_string()),
+            None => codegen_equation(tokens, start, end, location, other_doc_locations),
+        };
+    }
+
+    let mut row = codegen_equation(tokens, start, anchors[0], location, other_doc_locations)?;
+    for (j, anchor) in anchors.iter().enumerate() {
+        let segment_end = anchors.get(j + 1).copied().unwrap_or(end);
+        row = format!(
+            "{} &{}",
+            row,
+            codegen_equation(
+                tokens,
+                anchor + 1,
+                segment_end,
+                location,
+                other_doc_locations
+            )?
+        );
+    }
+    Ok(row.trim().to_string())
+}
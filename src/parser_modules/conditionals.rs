@@ -0,0 +1,234 @@
+use crate::bracket_depth::BrackDepths;
+use crate::document::{DocSection, NodeList};
+use crate::parse::{node_list, CompilerGlobals, IndentationType, NodeParser, ParseResult};
+use crate::parser_modules::loops::next_meaningful;
+use crate::parser_modules::variables::{dedent, evaluate_at_expression};
+use crate::token::*;
+use crate::tokenize::TokenList;
+use crate::typed_value::TypedValue;
+use crate::utils::delta_bracket_depth;
+
+/// `if @SOLUTIONS { ... } else if @(YEAR > 2025) { ... } else { ... }`, decided at compile time.
+#[derive(Default)]
+pub struct LiaIf {
+    curly_depth: i32,
+}
+
+struct Branch<'a> {
+    condition: Option<TokenList<'a>>,
+    body: TokenList<'a>,
+}
+
+#[allow(unused)]
+impl NodeParser for LiaIf {
+    fn is_opener(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        identation: i32,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> bool {
+        self.curly_depth = -1;
+        if !is_keyword(&tokens[cursor], "if") {
+            return false;
+        }
+        let Some(condition) = next_meaningful(tokens, cursor + 1) else {
+            return false;
+        };
+        // Prose like "if @name is set" has no body on the same line.
+        matches!(tokens[condition], Token::LiaVariable(_, _))
+            && tokens[condition..]
+                .iter()
+                .take_while(|t| !t.is_newline())
+                .any(|t| is_keyword(t, "{"))
+    }
+
+    fn is_closer(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        bracket_depths: &BrackDepths,
+        start_bracket_depths: &BrackDepths,
+    ) -> bool {
+        if self.curly_depth == -1 {
+            self.curly_depth = bracket_depths.curly;
+        }
+        is_keyword(&tokens[cursor], "}")
+            && bracket_depths.curly == self.curly_depth
+            && !continues_with_else(tokens, cursor + 1)
+    }
+
+    fn parse(
+        &mut self,
+        tokens: &[Token],
+        range_start: usize,
+        range_end: usize,
+        indentation_type: Option<IndentationType>,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> ParseResult {
+        let tokens = &tokens[range_start..=range_end];
+        let branches = split_branches(tokens)?;
+
+        // Every condition is checked, so both variants of a document fail on the same mistakes.
+        let mut taken: Option<usize> = None;
+        for (i, branch) in branches.iter().enumerate() {
+            let is_true = match branch.condition {
+                Some(condition) => evaluate_condition(condition, other_doc_locations)?,
+                None => true,
+            };
+            if is_true && taken.is_none() {
+                taken = Some(i);
+            }
+        }
+
+        let mut bodies: Vec<Vec<Token>> = branches
+            .iter()
+            .map(|branch| {
+                let mut body = dedent(branch.body);
+                // A body on lines of its own is kept on its own lines, otherwise within the line.
+                if branch.body.iter().any(|t| t.is_newline()) {
+                    body.push(Token::Newline);
+                }
+                body
+            })
+            .collect();
+        // Branches that are not used are still parsed to catch their errors, before the
+        // declarations of the used one exist.
+        for (i, body) in bodies.iter().enumerate() {
+            if taken != Some(i) {
+                let checkpoint = other_doc_locations.checkpoint();
+                let result = node_list(body, 0, body.len(), other_doc_locations);
+                other_doc_locations.rollback(checkpoint);
+                result?;
+            }
+        }
+        let nodes: NodeList = match taken {
+            Some(i) => {
+                let body = bodies.swap_remove(i);
+                node_list(&body, 0, body.len(), other_doc_locations)?
+            }
+            None => Vec::new(),
+        };
+        Ok((nodes, DocSection::Document))
+    }
+}
+
+fn is_keyword(token: &Token, keyword: &str) -> bool {
+    matches!(token, Token::Misc(t, _) if t == keyword)
+}
+
+/// Whether the `}` before `cursor` is followed by `else {` or `else if`, possibly on the next line.
+fn continues_with_else(tokens: TokenList, cursor: usize) -> bool {
+    let Some(keyword) = (cursor..tokens.len())
+        .find(|i| !matches!(tokens[*i], Token::Whitespace(_) | Token::Newline))
+    else {
+        return false;
+    };
+    is_keyword(&tokens[keyword], "else")
+        && next_meaningful(tokens, keyword + 1)
+            .is_some_and(|i| is_keyword(&tokens[i], "{") || is_keyword(&tokens[i], "if"))
+}
+
+/// Splits `if ... { } else if ... { } else { }` into its conditions and bodies.
+fn split_branches(tokens: TokenList) -> Result<Vec<Branch>, String> {
+    let mut branches: Vec<Branch> = Vec::new();
+    let mut start = 0;
+    loop {
+        let location = tokens[start].get_location();
+        let Some(open) = (start..tokens.len()).find(|i| is_keyword(&tokens[*i], "{")) else {
+            return Err(format!(
+                "{} Expected the body of the branch in {{}}.",
+                location.stringify()
+            ));
+        };
+        let header = &tokens[start + 1..open];
+        let condition = if is_keyword(&tokens[start], "if") {
+            if dedent(header).is_empty() {
+                return Err(format!(
+                    "{} Expected a condition after \"else if\".",
+                    location.stringify()
+                ));
+            }
+            Some(header)
+        } else if dedent(header).is_empty() {
+            None
+        } else {
+            return Err(format!(
+                "{} Expected \"{{\" or \"if\" after \"else\".",
+                location.stringify()
+            ));
+        };
+
+        let mut depth = BrackDepths::default();
+        let close = (open..tokens.len())
+            .find(|i| {
+                depth += delta_bracket_depth(&tokens[*i]);
+                depth.curly == 0
+            })
+            .unwrap_or(tokens.len() - 1);
+        branches.push(Branch {
+            condition,
+            body: &tokens[open + 1..close],
+        });
+        if close + 1 >= tokens.len() {
+            return Ok(branches);
+        }
+
+        let keyword = (close + 1..tokens.len())
+            .find(|i| !matches!(tokens[*i], Token::Whitespace(_) | Token::Newline))
+            .unwrap();
+        if condition.is_none() {
+            return Err(format!(
+                "{} Found \"else\" after \"else {{...}}\", which must be the last branch.",
+                tokens[keyword].get_location().stringify()
+            ));
+        }
+        let next = next_meaningful(tokens, keyword + 1).unwrap();
+        start = if is_keyword(&tokens[next], "if") {
+            next
+        } else {
+            keyword
+        };
+    }
+}
+
+fn evaluate_condition(
+    condition: TokenList,
+    other_doc_locations: &CompilerGlobals,
+) -> Result<bool, String> {
+    let condition = dedent(condition);
+    let location = condition[0].get_location();
+    for t in &condition {
+        if let Token::LiaVariable(name, location) = t {
+            let name = &name[1..];
+            if !name.is_empty() && other_doc_locations.constant(name).is_none() {
+                return Err(format!(
+                    "{} \"@{}\" is not defined. Declare it in the document or pass \"-D {}=...\" to the compiler.",
+                    location.stringify(),
+                    name,
+                    name
+                ));
+            }
+        }
+    }
+    // `@SOLUTIONS` and `@(YEAR > 2025)` are both read as one expression.
+    let mut expression = vec![
+        Token::LiaVariable("@".to_string(), location),
+        Token::Misc("(".to_string(), location),
+    ];
+    expression.extend(
+        condition
+            .into_iter()
+            .filter(|t| !matches!(t, Token::LiaVariable(at, _) if at == "@")),
+    );
+    expression.push(Token::Misc(")".to_string(), location));
+    match evaluate_at_expression(&expression, other_doc_locations)?.0 {
+        TypedValue::Bool(b) => Ok(b),
+        value => Err(format!(
+            "{} Expected the condition to be true or false, found {} \"{}\".",
+            location.stringify(),
+            value.type_name(),
+            value.stringify()
+        )),
+    }
+}
@@ -0,0 +1,220 @@
+use std::rc::Rc;
+
+use crate::bracket_depth::BrackDepths;
+use crate::document::{DocSection, TexEnvironment, Text};
+use crate::parse::{node_list, CompilerGlobals, IndentationType, NodeParser, ParseResult};
+use crate::parser_modules::list::{append_closer, nest_to_indentation};
+use crate::token::*;
+use crate::tokenize::TokenList;
+use crate::utils::{count_indentation, delta_bracket_depth, is_start_of_line};
+
+#[derive(Default)]
+pub struct LiaMarkdownDescriptionListParser {
+    initial_indentation_depth: usize,
+}
+
+enum LineType {
+    Definition(usize),     // : definition
+    TermDefinition(usize), // term :: definition
+    Term,                  // term followed by a definition line
+}
+
+impl NodeParser for LiaMarkdownDescriptionListParser {
+    fn is_opener(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        identation: i32,
+        _other_doc_locations: &mut CompilerGlobals,
+    ) -> bool {
+        if matches!(tokens[cursor], Token::Whitespace(_) | Token::Newline)
+            || !is_start_of_line(tokens, cursor)
+        {
+            return false;
+        }
+        match classify_line(tokens, cursor, false) {
+            Some(LineType::TermDefinition(_)) | Some(LineType::Term) => {
+                self.initial_indentation_depth = identation as usize;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn is_closer(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        bracket_depths: &BrackDepths,
+        start_bracket_depths: &BrackDepths,
+    ) -> bool {
+        bracket_depths.curly == start_bracket_depths.curly
+            && tokens[cursor].is_newline()
+            && classify_line(tokens, cursor + 1, true).is_none()
+    }
+
+    fn parse(
+        &mut self,
+        tokens: &[Token],
+        range_start: usize,
+        range_end: usize,
+        indentation_type: Option<IndentationType>,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> ParseResult {
+        let tokens = &tokens[range_start..=range_end];
+
+        let mut indentation: usize = self.initial_indentation_depth;
+        let mut indentation_type = indentation_type;
+        let mut pre_indentation = self.initial_indentation_depth;
+        let mut inner_nodes: Vec<Token> = vec![Token::Newline];
+        let mut line_start = 0;
+        while line_start < tokens.len() {
+            count_indentation(&tokens, line_start, &mut indentation, &mut indentation_type);
+            let line_end = find_line_end(tokens, line_start);
+            let first = first_non_whitespace(tokens, line_start, line_end);
+            let loc = tokens[first.min(tokens.len() - 1)].get_location();
+            match classify_line(tokens, line_start, true) {
+                Some(LineType::Definition(colon)) => {
+                    inner_nodes.push(Token::Whitespace(" ".to_string()));
+                    push_trimmed(&mut inner_nodes, tokens, colon + 1, line_end);
+                    inner_nodes.push(Token::Newline);
+                }
+                Some(LineType::TermDefinition(separator)) => {
+                    nest_to_indentation(
+                        indentation,
+                        &mut pre_indentation,
+                        &loc,
+                        &mut inner_nodes,
+                        "description",
+                        None,
+                    )?;
+                    push_item(&mut inner_nodes, tokens, first, separator);
+                    inner_nodes.push(Token::Whitespace(" ".to_string()));
+                    push_trimmed(&mut inner_nodes, tokens, separator + 1, line_end);
+                    inner_nodes.push(Token::Newline);
+                }
+                Some(LineType::Term) => {
+                    nest_to_indentation(
+                        indentation,
+                        &mut pre_indentation,
+                        &loc,
+                        &mut inner_nodes,
+                        "description",
+                        None,
+                    )?;
+                    push_item(&mut inner_nodes, tokens, first, line_end);
+                }
+                None => {}
+            }
+            line_start = line_end + 1;
+        }
+        while pre_indentation > self.initial_indentation_depth {
+            append_closer(&mut inner_nodes, "description");
+            pre_indentation -= 1;
+        }
+        Ok((
+            vec![
+                Rc::new(TexEnvironment {
+                    name: "description".to_string(),
+                    args: vec![],
+                    children: node_list(&inner_nodes, 0, inner_nodes.len(), other_doc_locations)?,
+                }),
+                Rc::new(Text {
+                    text: "\n".to_string(),
+                }),
+            ],
+            DocSection::Document,
+        ))
+    }
+}
+
+/// Outside a list, `term :: definition` lines only start one as a block of at least two lines,
+/// so prose like `Note :: see below` stays text. A term on its own line followed by a
+/// `: definition` line always starts one.
+fn classify_line(tokens: TokenList, line_start: usize, in_list: bool) -> Option<LineType> {
+    let line_end = find_line_end(tokens, line_start);
+    let first = first_non_whitespace(tokens, line_start, line_end);
+    if first >= line_end {
+        return None;
+    }
+    let is_spaced = |i: usize| {
+        matches!(tokens.get(i + 1), Some(Token::Whitespace(_)))
+            && i > 0
+            && matches!(tokens[i - 1], Token::Whitespace(_))
+    };
+    match &tokens[first] {
+        Token::Misc(t, _) if t == ":" => {
+            return if matches!(tokens.get(first + 1), Some(Token::Whitespace(_))) {
+                Some(LineType::Definition(first))
+            } else {
+                None
+            };
+        }
+        Token::LiaMarkDown(_, _) | Token::LiaKeyword(_, _) => return None,
+        _ => {}
+    }
+    let mut brack_depth = BrackDepths::default();
+    for (i, token) in tokens.iter().enumerate().take(line_end).skip(first) {
+        brack_depth += delta_bracket_depth(token);
+        if let Token::Misc(t, _) = token {
+            if t == "::" && brack_depth.is_zero() && is_spaced(i) {
+                let followed_by_item = line_end < tokens.len()
+                    && matches!(
+                        classify_line(tokens, line_end + 1, true),
+                        Some(LineType::TermDefinition(_))
+                    );
+                return (in_list || followed_by_item).then_some(LineType::TermDefinition(i));
+            }
+        }
+    }
+    if line_end < tokens.len() {
+        if let Some(LineType::Definition(_)) = classify_line(tokens, line_end + 1, in_list) {
+            return Some(LineType::Term);
+        }
+    }
+    None
+}
+
+/// Finds the newline ending the line, skipping newlines inside `{}`.
+fn find_line_end(tokens: TokenList, line_start: usize) -> usize {
+    let mut brack_depth = BrackDepths::default();
+    for (i, token) in tokens.iter().enumerate().skip(line_start) {
+        brack_depth += delta_bracket_depth(token);
+        if token.is_newline() && brack_depth.curly <= 0 {
+            return i;
+        }
+    }
+    tokens.len()
+}
+
+fn first_non_whitespace(tokens: TokenList, start: usize, end: usize) -> usize {
+    let mut i = start;
+    while i < end {
+        if let Token::Whitespace(_) = tokens[i] {
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    i
+}
+
+fn push_item(inner_nodes: &mut Vec<Token>, tokens: TokenList, start: usize, end: usize) {
+    inner_nodes.push(Token::TexCommand("\\item".to_string(), Location::default()));
+    inner_nodes.push(Token::Misc("[".to_string(), Location::default()));
+    push_trimmed(inner_nodes, tokens, start, end);
+    inner_nodes.push(Token::Misc("]".to_string(), Location::default()));
+}
+
+fn push_trimmed(inner_nodes: &mut Vec<Token>, tokens: TokenList, start: usize, end: usize) {
+    let start = first_non_whitespace(tokens, start, end);
+    let mut end = end;
+    while end > start {
+        if let Token::Whitespace(_) = tokens[end - 1] {
+            end -= 1;
+        } else {
+            break;
+        }
+    }
+    inner_nodes.extend_from_slice(&tokens[start..end]);
+}
@@ -1,11 +1,11 @@
 use std::rc::Rc;
 
 use crate::bracket_depth::BrackDepths;
-use crate::document::{DocSection, Node, TexEnvironment, Text};
+use crate::document::{DocSection, TexEnvironment, Text};
 use crate::parse::{node_list, CompilerGlobals, IndentationType, NodeParser, ParseResult};
+use crate::parser_modules::list::{append_closer, custom_marker, env_args, list_item, ListItem};
 use crate::token::*;
-use crate::tokenize::TokenList;
-use crate::utils::{count_indentation, delta_bracket_depth, format_error_string};
+use crate::utils::{count_indentation, delta_bracket_depth, is_start_of_line};
 
 #[derive(Default)]
 pub struct LiaMardownEnumListParser {
@@ -43,7 +43,7 @@ impl NodeParser for LiaMardownEnumListParser {
         }
         match token {
             Token::Misc(text, _) => {
-                if is_list_number(text.to_string()) {
+                if is_list_opener(text) {
                     self.initial_indentation_depth = identation as usize;
                     true
                 } else {
@@ -68,7 +68,7 @@ impl NodeParser for LiaMardownEnumListParser {
         bracket_depths.curly == start_bracket_depths.curly
             && match token {
                 Token::Newline => match next_token_no_white_space {
-                    Token::Misc(_, _) => !is_list_number(next_token_no_white_space.stringify()),
+                    Token::Misc(text, _) => numbering_style(text).is_none(),
                     _ => true,
                 },
                 _ => false,
@@ -89,45 +89,77 @@ impl NodeParser for LiaMardownEnumListParser {
         let mut indentation_type = indentation_type;
         let mut pre_indentation = self.initial_indentation_depth;
         let mut item_count = 0;
+        let mut env_options = None;
+        let mut styled = false;
         let mut inner_nodes: Vec<Token> = vec![Token::Newline];
         let mut brack_depth = BrackDepths::default();
+        let mut skip_to = 0;
         for i in 0..tokens.len() {
             brack_depth += delta_bracket_depth(&tokens[i]);
             if item_count > 0 {
                 count_indentation(&tokens, i, &mut indentation, &mut indentation_type);
             }
+            if i < skip_to {
+                continue;
+            }
             match &tokens[i] {
-                Token::Misc(t, loc) => {
-                    if is_list_number(t.to_string()) && brack_depth.curly == 0 {
+                Token::Misc(t, loc) => match numbering_style(t) {
+                    Some(mut style) if brack_depth.curly == 0 && is_start_of_line(tokens, i) => {
+                        let mut item = ListItem::new("enumerate");
+                        skip_to = i + 1;
+                        if let NumberingStyle::Arabic = style {
+                            // `1.(a)` numbers the list it opens with letters.
+                            if let Some((marker, end)) = custom_marker(tokens, i) {
+                                if let Some(chosen) = numbering_style(&format!("{}.", marker)) {
+                                    style = chosen;
+                                    skip_to = end + 1;
+                                }
+                            }
+                        }
+                        if item_count == 0 || indentation > pre_indentation {
+                            // Once a lettered style is used, nested `1.` lists must be explicit
+                            // too or they would get LaTeX's default label for their depth.
+                            let label = match style {
+                                NumberingStyle::Arabic if styled => Some("\\arabic*."),
+                                _ => style.label(),
+                            };
+                            item.env_options = label.map(|label| {
+                                other_doc_locations.require_package("enumitem");
+                                styled = true;
+                                format!("label={}", label)
+                            });
+                        }
+                        if item_count == 0 {
+                            env_options = item.env_options.take();
+                        }
                         if let Some(value) = list_item(
                             &mut item_count,
                             indentation,
                             &mut pre_indentation,
                             loc,
                             &mut inner_nodes,
-                            i,
-                            tokens,
+                            tokens.get(skip_to),
+                            &item,
                         ) {
                             return value;
                         }
-                    } else {
-                        inner_nodes.push(tokens[i].clone());
                     }
-                }
+                    _ => inner_nodes.push(tokens[i].clone()),
+                },
                 _ => {
                     inner_nodes.push(tokens[i].clone());
                 }
             }
         }
         while pre_indentation > self.initial_indentation_depth {
-            append_closer(&mut inner_nodes);
+            append_closer(&mut inner_nodes, "enumerate");
             pre_indentation -= 1;
         }
         Ok((
             vec![
                 Rc::new(TexEnvironment {
                     name: "enumerate".to_string(),
-                    args: vec![],
+                    args: env_args(env_options),
                     children: node_list(&inner_nodes, 0, inner_nodes.len(), other_doc_locations)?,
                 }),
                 Rc::new(Text {
@@ -139,67 +171,52 @@ impl NodeParser for LiaMardownEnumListParser {
     }
 }
 
-fn list_item(
-    item_count: &mut i32,
-    indentation: usize,
-    pre_indentation: &mut usize,
-    loc: &Location,
-    inner_nodes: &mut Vec<Token>,
-    i: usize,
-    tokens: TokenList,
-) -> Option<Result<(Vec<Rc<dyn Node>>, DocSection), String>> {
-    *item_count += 1;
-    if indentation > *pre_indentation {
-        if indentation - *pre_indentation > 1 {
-            return Some(format_error_string(
-                "Indentation error. Nested item list was indented too far.".to_string(),
-                *loc,
-            ));
-        }
-        append_opener(inner_nodes);
-    } else if indentation < *pre_indentation {
-        let diff = *pre_indentation - indentation;
-        for _ in 0..diff {
-            append_closer(inner_nodes);
-        }
-    }
-    inner_nodes.push(Token::TexCommand("\\item".to_string(), Location::default()));
-    if i + 1 < tokens.len() {
-        if let Token::Whitespace(_) = &tokens[i + 1] {
-            {}
-        } else {
-            inner_nodes.push(Token::Whitespace(" ".to_string()));
-        }
-    }
-    *pre_indentation = indentation;
-    None
+enum NumberingStyle {
+    Arabic,     // 1.
+    LowerAlpha, // a.
+    UpperAlpha, // A.
+    LowerRoman, // i.
+    UpperRoman, // I.
 }
 
-fn append_opener(inner_nodes: &mut Vec<Token>) {
-    inner_nodes.push(Token::TexCommand(
-        "\\begin".to_string(),
-        Location::default(),
-    ));
-    inner_nodes.push(Token::Misc("{".to_string(), Location::default()));
-    inner_nodes.push(Token::Misc("enumerate".to_string(), Location::default()));
-    inner_nodes.push(Token::Misc("}".to_string(), Location::default()));
-    inner_nodes.push(Token::Newline);
+impl NumberingStyle {
+    /// The `enumitem` label, `None` keeps the LaTeX default.
+    fn label(&self) -> Option<&'static str> {
+        match self {
+            NumberingStyle::Arabic => None,
+            NumberingStyle::LowerAlpha => Some("\\alph*."),
+            NumberingStyle::UpperAlpha => Some("\\Alph*."),
+            NumberingStyle::LowerRoman => Some("\\roman*."),
+            NumberingStyle::UpperRoman => Some("\\Roman*."),
+        }
+    }
 }
 
-fn append_closer(inner_nodes: &mut Vec<Token>) {
-    inner_nodes.push(Token::TexCommand("\\end".to_string(), Location::default()));
-    inner_nodes.push(Token::Misc("{".to_string(), Location::default()));
-    inner_nodes.push(Token::Misc("enumerate".to_string(), Location::default()));
-    inner_nodes.push(Token::Misc("}".to_string(), Location::default()));
-    inner_nodes.push(Token::Newline);
+/// Only `1.` can start a list, so prose like `I. Newton` or `A. Smith` stays text. A lettered list
+/// is opted into with `1.(a)`, or nested in a numbered one.
+fn is_list_opener(text: &str) -> bool {
+    matches!(numbering_style(text), Some(NumberingStyle::Arabic))
 }
 
-fn is_list_number(text: String) -> bool {
-    if !text.ends_with('.') {
-        return false;
+fn numbering_style(text: &str) -> Option<NumberingStyle> {
+    let marker = text.strip_suffix('.')?;
+    if marker.is_empty() {
+        return None;
     }
-    if !text.starts_with(|c: char| c.is_numeric()) {
-        return false;
+    let roman = |digits: &str| marker.len() > 1 && marker.chars().all(|c| digits.contains(c));
+    if marker.starts_with(|c: char| c.is_numeric())
+        && marker.chars().all(|c| c.is_numeric() || c == '.')
+    {
+        Some(NumberingStyle::Arabic)
+    } else if marker == "i" || roman("ivxlcdm") {
+        Some(NumberingStyle::LowerRoman)
+    } else if marker == "I" || roman("IVXLCDM") {
+        Some(NumberingStyle::UpperRoman)
+    } else if marker.len() == 1 && marker.chars().all(|c| c.is_ascii_lowercase()) {
+        Some(NumberingStyle::LowerAlpha)
+    } else if marker.len() == 1 && marker.chars().all(|c| c.is_ascii_uppercase()) {
+        Some(NumberingStyle::UpperAlpha)
+    } else {
+        None
     }
-    text.chars().all(|c| c.is_numeric() || c == '.')
 }
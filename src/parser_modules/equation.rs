@@ -3,8 +3,13 @@ use std::rc::Rc;
 use crate::ast::Ast;
 use crate::at_expression::AtExpToken;
 use crate::bracket_depth::BrackDepths;
-use crate::document::{DocSection, Node, TexEnvironment, Text};
+use crate::document::{DocSection, LabelKind, Node, TexEnvironment, Text};
+use crate::grammar::cases::mark_case_keywords;
+use crate::grammar::vector::mark_matrix_keywords;
+use crate::number_format::format_value;
 use crate::parse::{node_list, CompilerGlobals, IndentationType, NodeParser, ParseResult};
+use crate::parser_modules::inline_math::require_unicode_math_packages;
+use crate::parser_modules::labels::{label_command, parse_label_name, register_label};
 use crate::token::*;
 use crate::tokenize::TokenList;
 use crate::utils::format_error_string;
@@ -14,8 +19,8 @@ pub struct LiaEquation {
     curly_depth: i32,
 }
 
-static OPERATORS_AND_KEYWORDS: [&str; 15] = [
-    "+", "-", "*", "/", "%", "?", ":", "(", ")", "{", "}", "^", ",", "[", "]",
+static OPERATORS_AND_KEYWORDS: [&str; 16] = [
+    "+", "-", "*", "/", "%", "?", ":", "(", ")", "{", "}", "^", "_", ",", "[", "]",
 ];
 
 #[allow(unused)]
@@ -63,8 +68,11 @@ impl NodeParser for LiaEquation {
         other_doc_locations: &mut CompilerGlobals,
     ) -> ParseResult {
         let tokens = &tokens[range_start..=range_end];
+        require_unicode_math_packages(tokens, other_doc_locations);
+        require_cases_package(tokens, other_doc_locations);
 
         let mut asterisk = false;
+        let mut label: Option<String> = None;
         let mut open_pos = 1;
         let len = tokens.len();
         while open_pos < len {
@@ -74,6 +82,16 @@ impl NodeParser for LiaEquation {
                 if t == "*" {
                     asterisk = true;
                     open_pos += 1;
+                } else if t == "#" && label.is_none() {
+                    let label_start = open_pos + 1;
+                    open_pos = label_start;
+                    while open_pos < len {
+                        match &tokens[open_pos] {
+                            Token::Misc(t, _) if t != "{" => open_pos += 1,
+                            _ => break,
+                        }
+                    }
+                    label = Some(parse_label_name(&tokens[label_start..open_pos], *loc)?);
                 } else if t == "{" {
                     break;
                 } else {
@@ -90,28 +108,53 @@ impl NodeParser for LiaEquation {
             }
         }
 
-        let children = if contains_anything_meaningful(&tokens, open_pos + 1, len - 1)
+        if let Some(label) = &label {
+            if asterisk {
+                return format_error_string(
+                    format! {"Anonymous equations can not be labelled, found label \"{label}\"."},
+                    tokens[0].get_location(),
+                );
+            }
+            register_label(
+                label.clone(),
+                LabelKind::Equation,
+                tokens[0].get_location(),
+                other_doc_locations,
+            )?;
+        }
+
+        let mut children = if contains_anything_meaningful(&tokens, open_pos + 1, len - 1)
             && other_doc_locations
                 .feature_status_list
                 .equation_statement_internal_syntax
                 .is_supported()
         {
             vec![Rc::new(Text {
-                text: Ast::construct(
-                    &to_at_exp_tokens_for_equation(&tokens, open_pos + 1, len - 1)?,
-                    0,
-                    format!(
-                        "{} Invalid syntax in equation statement",
-                        tokens[0].get_location().stringify()
-                    )
-                    .as_str(),
-                )?
-                .codegen(),
+                text: codegen_equation(
+                    tokens,
+                    open_pos + 1,
+                    len - 1,
+                    tokens[0].get_location(),
+                    other_doc_locations,
+                )?,
             }) as Rc<dyn Node>]
         } else {
-            node_list(tokens, open_pos + 1, len - 1, other_doc_locations)?
+            let outside_math = std::mem::replace(&mut other_doc_locations.in_math, true);
+            let content = node_list(tokens, open_pos + 1, len - 1, other_doc_locations);
+            other_doc_locations.in_math = outside_math;
+            content?
         };
 
+        if let Some(label) = label {
+            children.insert(
+                0,
+                Rc::new(Text {
+                    text: "\n".to_string(),
+                }),
+            );
+            children.insert(0, label_command(label));
+        }
+
         Ok((
             vec![Rc::new(TexEnvironment {
                 name: if asterisk {
@@ -127,22 +170,93 @@ impl NodeParser for LiaEquation {
     }
 }
 
+/// Imports `amsmath` if the equation has a `cases {}` block.
+pub fn require_cases_package(tokens: TokenList, other_doc_locations: &mut CompilerGlobals) {
+    if tokens
+        .iter()
+        .any(|t| matches!(t, Token::Misc(t, _) if t == "cases"))
+    {
+        other_doc_locations.require_package("amsmath");
+    }
+}
+
+/// Runs a range of tokens through the equation internal syntax.
+pub fn codegen_equation(
+    tokens: TokenList,
+    start: usize,
+    end: usize,
+    location: Location,
+    other_doc_locations: &CompilerGlobals,
+) -> Result<String, String> {
+    if !contains_anything_meaningful(&tokens, start, end) {
+        return Ok(String::new());
+    }
+    let at_exp_tokens = to_at_exp_tokens_for_equation(&tokens, start, end, other_doc_locations)?;
+    if at_exp_tokens.is_empty() {
+        return Err(format!(
+            "{} Expected an expression in equation statement, found none.",
+            location.stringify()
+        ));
+    }
+    Ok(Ast::construct(
+        &at_exp_tokens,
+        0,
+        format!(
+            "{} Invalid syntax in equation statement",
+            location.stringify()
+        )
+        .as_str(),
+    )?
+    .codegen())
+}
+
 fn to_at_exp_tokens_for_equation(
     tokens: &TokenList,
     start: usize,
     end: usize,
+    other_doc_locations: &CompilerGlobals,
 ) -> Result<Vec<AtExpToken>, String> {
     let mut at_exp_tokens = vec![];
     for i in start..end {
-        let t_opt = tokenise(&tokens[i])?;
+        if let Token::Misc(t, loc) = &tokens[i] {
+            for part in split_symbols(t) {
+                if let Some(t) =
+                    tokenise(&Token::Misc(part.to_string(), *loc), other_doc_locations)?
+                {
+                    at_exp_tokens.push(t);
+                }
+            }
+            continue;
+        }
+        let t_opt = tokenise(&tokens[i], other_doc_locations)?;
         if let Some(t) = t_opt {
             at_exp_tokens.push(t);
         }
     }
+    mark_case_keywords(&mut at_exp_tokens);
+    mark_matrix_keywords(&mut at_exp_tokens);
     Ok(at_exp_tokens)
 }
 
-fn tokenise(token: &Token) -> Result<Option<AtExpToken>, String> {
+/// Splits operators the tokenizer grouped with other symbols, e.g. `^-` in `e^-x` or `:,`.
+fn split_symbols(t: &str) -> Vec<&str> {
+    if t.len() > 1 && (t.starts_with('^') || t.starts_with('_')) {
+        let mut parts = vec![&t[..1]];
+        parts.extend(split_symbols(&t[1..]));
+        parts
+    } else if t.len() > 1 && (t.ends_with(',') || t.ends_with(';')) {
+        let mut parts = split_symbols(&t[..t.len() - 1]);
+        parts.push(&t[t.len() - 1..]);
+        parts
+    } else {
+        vec![t]
+    }
+}
+
+fn tokenise(
+    token: &Token,
+    other_doc_locations: &CompilerGlobals,
+) -> Result<Option<AtExpToken>, String> {
     match token {
         Token::Misc(t, _) => {
             for op in OPERATORS_AND_KEYWORDS {
@@ -158,16 +272,25 @@ fn tokenise(token: &Token) -> Result<Option<AtExpToken>, String> {
         Token::LiaKeyword(t, loc) => {
             Err(format!("{} Unexpected keyword \"{}\" in equation statement. This will be supposed in future versions.", loc.stringify(), t))
         }
-        Token::LiaVariable(_, _) => {
-            Err("Variables are not current supported in equation statements outside of functions. This will be supported in the future.".to_string())
-        }
+        // Constants are replaced by their value.
+        Token::LiaVariable(var, loc) => match other_doc_locations.constant(&var[1..]) {
+            Some(value) => Ok(Some(AtExpToken::Text(
+                format_value(value, other_doc_locations.number_format.as_ref())
+                    .map_err(|e| format!("{} {}", loc.stringify(), e))?,
+            ))),
+            None => Err(format!(
+                "{} Unknown constant \"{}\" in equation statement.",
+                loc.stringify(),
+                var
+            )),
+        },
         _ => {
             Ok(None)
         }
     }
 }
 
-fn contains_anything_meaningful(tokens: &TokenList, start: usize, end: usize) -> bool {
+pub fn contains_anything_meaningful(tokens: &TokenList, start: usize, end: usize) -> bool {
     for i in start..end {
         match &tokens[i] {
             Token::Whitespace(_) => {}
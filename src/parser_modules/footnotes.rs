@@ -0,0 +1,267 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::bracket_depth::BrackDepths;
+use crate::document::*;
+use crate::parse::*;
+use crate::token::*;
+use crate::tokenize::TokenList;
+use crate::utils::{format_error_string, is_start_of_line, untokenise};
+
+pub struct FootnoteDefinition {
+    pub id: String,
+    pub content: NodeList,
+    pub location: Location,
+    pub used: bool,
+}
+
+pub struct PendingFootnote {
+    pub id: String,
+    pub number: usize,
+    pub location: Location,
+    pub content: Rc<RefCell<NodeList>>,
+}
+
+#[derive(Default, PartialEq)]
+enum FootnoteType {
+    #[default]
+    Reference, // [^id]
+    Inline,     // ^[content]
+    Definition, // [^id]: content
+}
+
+#[derive(Default)]
+pub struct FootnoteParser {
+    footnote_type: FootnoteType,
+}
+
+impl NodeParser for FootnoteParser {
+    fn is_opener(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        _identation: i32,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> bool {
+        if other_doc_locations.in_math {
+            return false;
+        }
+        let next = match tokens.get(cursor + 1) {
+            Some(Token::Misc(next, _)) => next,
+            _ => return false,
+        };
+        match &tokens[cursor] {
+            Token::Misc(t, _) if t == "^" && next == "[" => {
+                self.footnote_type = FootnoteType::Inline;
+                true
+            }
+            Token::Misc(t, _) if t == "[" && next.starts_with('^') => {
+                self.footnote_type =
+                    if is_start_of_line(tokens, cursor) && is_followed_by_colon(tokens, cursor) {
+                        FootnoteType::Definition
+                    } else {
+                        FootnoteType::Reference
+                    };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn is_closer(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        bracket_depths: &BrackDepths,
+        start_bracket_depths: &BrackDepths,
+    ) -> bool {
+        let token = &tokens[cursor];
+        match self.footnote_type {
+            FootnoteType::Reference => match token {
+                Token::Misc(t, _) => t == "]",
+                Token::Newline => true,
+                _ => false,
+            },
+            FootnoteType::Inline => match token {
+                Token::Misc(t, _) => {
+                    t == "]" && bracket_depths.square == start_bracket_depths.square
+                }
+                _ => false,
+            },
+            FootnoteType::Definition => match token {
+                Token::Newline => bracket_depths.curly == start_bracket_depths.curly,
+                _ => false,
+            },
+        }
+    }
+
+    fn parse(
+        &mut self,
+        tokens: &[Token],
+        range_start: usize,
+        range_end: usize,
+        _indentation_type: Option<IndentationType>,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> ParseResult {
+        let tokens = &tokens[range_start..=range_end];
+        let location = tokens[0].get_location();
+
+        match self.footnote_type {
+            FootnoteType::Inline => {
+                let content = node_list(tokens, 2, tokens.len() - 1, other_doc_locations)?;
+                Ok((
+                    vec![new_footnote(
+                        Rc::new(RefCell::new(content)),
+                        other_doc_locations,
+                    )],
+                    DocSection::Document,
+                ))
+            }
+            FootnoteType::Reference => {
+                if tokens.last().unwrap().is_newline() {
+                    return format_error_string(
+                        "Unclosed footnote reference.".to_string(),
+                        location,
+                    );
+                }
+                let id = parse_footnote_id(&tokens[1..tokens.len() - 1], location)?;
+                // Referencing a footnote again points to the same note.
+                if let Some(first) = other_doc_locations
+                    .footnote_references
+                    .iter()
+                    .find(|r| r.id == id)
+                {
+                    return Ok((
+                        vec![Rc::new(FootnoteMark {
+                            number: first.number,
+                        })],
+                        DocSection::Document,
+                    ));
+                }
+                let content = Rc::new(RefCell::new(vec![]));
+                other_doc_locations
+                    .footnote_references
+                    .push(PendingFootnote {
+                        id,
+                        number: other_doc_locations.footnotes.len() + 1,
+                        location,
+                        content: content.clone(),
+                    });
+                Ok((
+                    vec![new_footnote(content, other_doc_locations)],
+                    DocSection::Document,
+                ))
+            }
+            FootnoteType::Definition => {
+                let close = tokens.iter().position(|t| t.stringify() == "]").unwrap();
+                let id = parse_footnote_id(&tokens[1..close], location)?;
+                if let Some(existing) = other_doc_locations
+                    .footnote_definitions
+                    .iter()
+                    .find(|d| d.id == id)
+                {
+                    return format_error_string(
+                        format!(
+                            "Duplicate footnote \"{}\". It was first defined at {}.",
+                            id,
+                            existing.location.stringify()
+                        ),
+                        location,
+                    );
+                }
+                let mut start = close + 2;
+                while start < tokens.len() - 1 {
+                    if let Token::Whitespace(_) = tokens[start] {
+                        start += 1;
+                    } else {
+                        break;
+                    }
+                }
+                let content = node_list(tokens, start, tokens.len() - 1, other_doc_locations)?;
+                other_doc_locations
+                    .footnote_definitions
+                    .push(FootnoteDefinition {
+                        id,
+                        content,
+                        location,
+                        used: false,
+                    });
+                Ok((vec![], DocSection::Document))
+            }
+        }
+    }
+}
+
+fn new_footnote(
+    content: Rc<RefCell<NodeList>>,
+    other_doc_locations: &mut CompilerGlobals,
+) -> Rc<dyn Node> {
+    let footnote = Rc::new(Footnote {
+        number: other_doc_locations.footnotes.len() + 1,
+        content,
+    });
+    other_doc_locations.footnotes.push(footnote.clone());
+    footnote
+}
+
+fn is_followed_by_colon(tokens: TokenList, cursor: usize) -> bool {
+    for i in cursor + 1..tokens.len() {
+        match &tokens[i] {
+            Token::Misc(t, _) if t == "]" => {
+                return matches!(tokens.get(i + 1), Some(Token::Misc(t, _)) if t.starts_with(':'));
+            }
+            Token::Newline => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn parse_footnote_id(tokens: TokenList, location: Location) -> Result<String, String> {
+    let id = untokenise(tokens);
+    let id = id.trim_start_matches('^');
+    if id.is_empty() || id.contains(char::is_whitespace) {
+        return Err(format!(
+            "{} Invalid footnote id \"{}\".",
+            location.stringify(),
+            id
+        ));
+    }
+    Ok(id.to_string())
+}
+
+pub fn resolve_footnotes(other_doc_locations: &mut CompilerGlobals) -> Result<(), String> {
+    let mut errors: Vec<String> = Vec::new();
+    for reference in &other_doc_locations.footnote_references {
+        match other_doc_locations
+            .footnote_definitions
+            .iter_mut()
+            .find(|d| d.id == reference.id)
+        {
+            Some(definition) => {
+                definition.used = true;
+                *reference.content.borrow_mut() = definition.content.clone();
+            }
+            None => errors.push(format!(
+                "{} Reference to undefined footnote \"{}\".",
+                reference.location.stringify(),
+                reference.id
+            )),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    for definition in &other_doc_locations.footnote_definitions {
+        if !definition.used {
+            other_doc_locations.warn(
+                definition.location,
+                format!(
+                    "Footnote \"{}\" is defined but never referenced.",
+                    definition.id
+                ),
+            );
+        }
+    }
+    Ok(())
+}
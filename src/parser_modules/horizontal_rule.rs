@@ -0,0 +1,79 @@
+use std::rc::Rc;
+
+use crate::bracket_depth::BrackDepths;
+use crate::document::*;
+use crate::parse::*;
+use crate::token::*;
+use crate::utils::{is_start_of_line, move_past_whitespace};
+
+#[derive(Default)]
+pub struct LiaMarkdownHorizontalRule {}
+
+impl NodeParser for LiaMarkdownHorizontalRule {
+    fn is_opener(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        _identation: i32,
+        _other_doc_locations: &mut CompilerGlobals,
+    ) -> bool {
+        if let Token::Misc(t, _) = &tokens[cursor] {
+            return t.len() >= 3
+                && t.chars().all(|c| c == '-')
+                && is_start_of_line(tokens, cursor)
+                && move_past_whitespace(tokens, cursor + 1)
+                    .map(|t| t.is_newline())
+                    .unwrap_or(true);
+        }
+        false
+    }
+
+    fn is_closer(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        _bracket_depths: &BrackDepths,
+        _start_bracket_depths: &BrackDepths,
+    ) -> bool {
+        tokens[cursor].is_newline()
+    }
+
+    fn parse(
+        &mut self,
+        _tokens: &[Token],
+        _range_start: usize,
+        _range_end: usize,
+        _indentation_type: Option<IndentationType>,
+        _other_doc_locations: &mut CompilerGlobals,
+    ) -> ParseResult {
+        Ok((
+            vec![
+                Rc::new(TexCommand {
+                    command: "noindent".to_string(),
+                    args: vec![],
+                }),
+                Rc::new(TexCommand {
+                    command: "rule".to_string(),
+                    args: vec![
+                        Arg {
+                            arg: vec![Rc::new(Text {
+                                text: "\\linewidth".to_string(),
+                            })],
+                            arg_type: ArgType::Curly,
+                        },
+                        Arg {
+                            arg: vec![Rc::new(Text {
+                                text: "0.4pt".to_string(),
+                            })],
+                            arg_type: ArgType::Curly,
+                        },
+                    ],
+                }),
+                Rc::new(Text {
+                    text: "\n".to_string(),
+                }),
+            ],
+            DocSection::Document,
+        ))
+    }
+}
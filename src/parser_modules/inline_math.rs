@@ -0,0 +1,135 @@
+use std::rc::Rc;
+
+use crate::bracket_depth::BrackDepths;
+use crate::document::*;
+use crate::grammar::symbols::{required_package, unicode_math_replacment};
+use crate::parse::*;
+use crate::token::*;
+use crate::tokenize::TokenList;
+
+#[derive(Default)]
+pub struct LiaInlineMath {
+    start: usize,
+    display: bool,
+}
+
+impl NodeParser for LiaInlineMath {
+    fn is_opener(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        _identation: i32,
+        _other_doc_locations: &mut CompilerGlobals,
+    ) -> bool {
+        if !is_dollar(tokens.get(cursor)) {
+            return false;
+        }
+        self.start = cursor;
+        self.display = is_dollar(tokens.get(cursor + 1));
+        true
+    }
+
+    fn is_closer(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        _bracket_depths: &BrackDepths,
+        _start_bracket_depths: &BrackDepths,
+    ) -> bool {
+        let delimiter_len = if self.display { 2 } else { 1 };
+        if cursor < self.start + delimiter_len * 2 - 1 {
+            return false;
+        }
+        // Math can't span paragraphs so an unclosed `$` ends at a blank line.
+        if tokens[cursor].is_newline() {
+            return crate::utils::move_past_whitespace(tokens, cursor + 1)
+                .map(|t| t.is_newline())
+                .unwrap_or(true);
+        }
+        is_dollar(tokens.get(cursor)) && (!self.display || is_dollar(tokens.get(cursor - 1)))
+    }
+
+    fn parse(
+        &mut self,
+        tokens: &[Token],
+        range_start: usize,
+        range_end: usize,
+        _indentation_type: Option<IndentationType>,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> ParseResult {
+        let tokens = &tokens[range_start..=range_end];
+        if !is_dollar(tokens.last()) {
+            // Unclosed, leave as is.
+            let mut nodes = node_list(tokens, 1, tokens.len(), other_doc_locations)?;
+            nodes.insert(
+                0,
+                Rc::new(Text {
+                    text: "$".to_string(),
+                }),
+            );
+            return Ok((nodes, DocSection::Document));
+        }
+        let delimiter_len = if self.display { 2 } else { 1 };
+        // The closing delimiter is kept so TeX commands at the end can look ahead.
+        let inner = replace_unicode_math(&tokens[delimiter_len..], other_doc_locations);
+        let delimiter = "$".repeat(delimiter_len);
+
+        let mut nodes: NodeList = vec![Rc::new(Text {
+            text: delimiter.clone(),
+        })];
+        let outside_math = std::mem::replace(&mut other_doc_locations.in_math, true);
+        let content = node_list(&inner, 0, inner.len() - delimiter_len, other_doc_locations);
+        other_doc_locations.in_math = outside_math;
+        nodes.extend(content?);
+        nodes.push(Rc::new(Text { text: delimiter }));
+        Ok((nodes, DocSection::Document))
+    }
+}
+
+fn is_dollar(token: Option<&Token>) -> bool {
+    matches!(token, Some(Token::Misc(t, _)) if t == "$")
+}
+
+/// Replaces Unicode math symbols, e.g. `α` or `≤`, with their TeX commands.
+fn replace_unicode_math(
+    tokens: TokenList,
+    other_doc_locations: &mut CompilerGlobals,
+) -> Vec<Token> {
+    let mut ret = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match unicode_math_token(token) {
+            Some(replacment) => {
+                ret.push(Token::Misc(replacment.to_string(), token.get_location()));
+                let next_is_letter = matches!(
+                    tokens.get(i + 1),
+                    Some(Token::Misc(t, _)) if t.starts_with(|c: char| c.is_ascii_alphabetic())
+                );
+                if next_is_letter && replacment.ends_with(|c: char| c.is_ascii_alphabetic()) {
+                    ret.push(Token::Whitespace(" ".to_string()));
+                }
+            }
+            None => ret.push(token.clone()),
+        }
+    }
+    require_unicode_math_packages(tokens, other_doc_locations);
+    ret
+}
+
+fn unicode_math_token(token: &Token) -> Option<&'static str> {
+    if let Token::Misc(t, _) = token {
+        let mut chars = t.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return unicode_math_replacment(c);
+        }
+    }
+    None
+}
+
+/// Imports the packages needed by any Unicode math symbols in `tokens`.
+pub fn require_unicode_math_packages(tokens: TokenList, other_doc_locations: &mut CompilerGlobals) {
+    for token in tokens {
+        if let Some(package) = unicode_math_token(token).and_then(required_package) {
+            other_doc_locations.require_package(package);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::bracket_depth::BrackDepths;
+use crate::document::*;
+use crate::parse::*;
+use crate::token::*;
+use crate::tokenize::TokenList;
+use crate::utils::untokenise;
+
+pub struct Label {
+    pub name: String,
+    pub kind: LabelKind,
+    pub location: Location,
+}
+
+pub struct PendingReference {
+    pub label: String,
+    pub location: Location,
+    pub kind: Rc<Cell<LabelKind>>,
+}
+
+#[derive(Default)]
+pub struct LabelReferenceParser {}
+
+impl NodeParser for LabelReferenceParser {
+    fn is_opener(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        _identation: i32,
+        _other_doc_locations: &mut CompilerGlobals,
+    ) -> bool {
+        match (&tokens[cursor], tokens.get(cursor + 1)) {
+            (Token::Misc(open, _), Some(Token::Misc(hash, _))) if open == "[" && hash == "#" => {
+                reference_end(tokens, cursor).is_some()
+            }
+            _ => false,
+        }
+    }
+
+    fn is_closer(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        _bracket_depths: &BrackDepths,
+        _start_bracket_depths: &BrackDepths,
+    ) -> bool {
+        matches!(&tokens[cursor], Token::Misc(t, _) if t == "]")
+    }
+
+    fn parse(
+        &mut self,
+        tokens: &[Token],
+        range_start: usize,
+        range_end: usize,
+        _indentation_type: Option<IndentationType>,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> ParseResult {
+        let tokens = &tokens[range_start..=range_end];
+        let location = tokens[0].get_location();
+
+        let label = parse_label_name(&tokens[2..tokens.len() - 1], location)?;
+
+        let kind = Rc::new(Cell::new(LabelKind::default()));
+        other_doc_locations.label_references.push(PendingReference {
+            label: label.clone(),
+            location,
+            kind: kind.clone(),
+        });
+
+        Ok((
+            vec![Rc::new(LabelReference { label, kind })],
+            DocSection::Document,
+        ))
+    }
+}
+
+/// Finds the closing `]` of a `[#label]` reference starting at `cursor`. Returns `None` if the
+/// text between the brackets does not look like a label, e.g. `[#1]`, so it stays literal text.
+fn reference_end(tokens: &[Token], cursor: usize) -> Option<usize> {
+    let mut name = String::new();
+    for (i, token) in tokens.iter().enumerate().skip(cursor + 2) {
+        match token {
+            Token::Misc(t, _) if t == "]" => {
+                return is_label_like(&name).then_some(i);
+            }
+            Token::Newline | Token::Whitespace(_) => return None,
+            _ => name.push_str(&token.stringify()),
+        }
+    }
+    None
+}
+
+/// Labels start with a letter and contain letters, digits, `:`, `-`, `_` and `.`.
+fn is_label_like(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ':' | '-' | '_' | '.'))
+}
+
+pub fn parse_label_name(tokens: TokenList, location: Location) -> Result<String, String> {
+    let label = untokenise(tokens);
+    if label.is_empty() {
+        return Err(format!("{} Found empty label.", location.stringify()));
+    }
+    if label.contains(char::is_whitespace) {
+        return Err(format!(
+            "{} Labels can not contain whitespace, found \"{}\".",
+            location.stringify(),
+            label
+        ));
+    }
+    Ok(label)
+}
+
+pub fn label_command(label: String) -> Rc<dyn Node> {
+    Rc::new(TexCommand {
+        command: "label".to_string(),
+        args: vec![Arg {
+            arg: vec![Rc::new(Text { text: label })],
+            arg_type: ArgType::Curly,
+        }],
+    })
+}
+
+pub fn register_label(
+    name: String,
+    kind: LabelKind,
+    location: Location,
+    other_doc_locations: &mut CompilerGlobals,
+) -> Result<(), String> {
+    if let Some(existing) = other_doc_locations.labels.iter().find(|l| l.name == name) {
+        return Err(format!(
+            "{} Duplicate label \"{}\". It was first defined at {}.",
+            location.stringify(),
+            name,
+            existing.location.stringify()
+        ));
+    }
+    other_doc_locations.labels.push(Label {
+        name,
+        kind,
+        location,
+    });
+    Ok(())
+}
+
+pub fn resolve_label_references(other_doc_locations: &mut CompilerGlobals) -> Result<(), String> {
+    let mut errors: Vec<String> = Vec::new();
+    for reference in &other_doc_locations.label_references {
+        match other_doc_locations
+            .labels
+            .iter()
+            .find(|l| l.name == reference.label)
+        {
+            Some(label) => reference.kind.set(label.kind),
+            None => errors.push(format!(
+                "{} Reference to undefined label \"{}\".",
+                reference.location.stringify(),
+                reference.label
+            )),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    let kinds: Vec<LabelKind> = other_doc_locations
+        .label_references
+        .iter()
+        .map(|r| r.kind.get())
+        .collect();
+    if kinds.contains(&LabelKind::Equation) {
+        other_doc_locations.require_package("amsmath");
+    }
+    if kinds.contains(&LabelKind::Other) {
+        other_doc_locations.require_package("cleveref");
+    }
+    Ok(())
+}
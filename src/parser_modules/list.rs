@@ -1,11 +1,11 @@
 use std::rc::Rc;
 
 use crate::bracket_depth::BrackDepths;
-use crate::document::{DocSection, Node, TexEnvironment, Text};
+use crate::document::{Arg, ArgType, DocSection, Node, TexEnvironment, Text};
 use crate::parse::{node_list, CompilerGlobals, IndentationType, NodeParser, ParseResult};
 use crate::token::*;
 use crate::tokenize::TokenList;
-use crate::utils::{count_indentation, delta_bracket_depth, format_error_string};
+use crate::utils::{count_indentation, delta_bracket_depth, untokenise};
 
 #[derive(Default)]
 pub struct LiaMardownListParser {
@@ -68,24 +68,52 @@ impl NodeParser for LiaMardownListParser {
         let mut indentation_type = indentation_type;
         let mut pre_indentation = self.initial_indentation_depth;
         let mut item_count = 0;
+        let mut env_options = None;
         let mut inner_nodes: Vec<Token> = vec![Token::Newline];
         let mut brack_depth = BrackDepths::default();
+        let mut skip_to = 0;
         for i in 0..tokens.len() {
             brack_depth += delta_bracket_depth(&tokens[i]);
             if item_count > 0 {
                 count_indentation(&tokens, i, &mut indentation, &mut indentation_type);
             }
+            if i < skip_to {
+                continue;
+            }
             match &tokens[i] {
                 Token::LiaMarkDown(md, loc) => {
                     if md == "*" && brack_depth.curly == 0 {
+                        let opens_list = item_count == 0 || indentation > pre_indentation;
+                        let mut item = ListItem::new("itemize");
+                        skip_to = i + 1;
+                        if let Some((marker, end)) = custom_marker(tokens, i) {
+                            other_doc_locations.require_package("enumitem");
+                            if opens_list {
+                                item.env_options = Some(format!("label={}", marker));
+                            } else {
+                                item.label = Some(marker);
+                            }
+                            skip_to = end + 1;
+                        } else if let Some((checked, end)) = task_marker(tokens, i) {
+                            other_doc_locations.require_package("amssymb");
+                            item.label = Some(if checked {
+                                "$\\boxtimes$".to_string()
+                            } else {
+                                "$\\square$".to_string()
+                            });
+                            skip_to = end + 1;
+                        }
+                        if item_count == 0 {
+                            env_options = item.env_options.take();
+                        }
                         if let Some(value) = list_item(
                             &mut item_count,
                             indentation,
                             &mut pre_indentation,
                             loc,
                             &mut inner_nodes,
-                            i,
-                            &tokens,
+                            tokens.get(skip_to),
+                            &item,
                         ) {
                             return value;
                         }
@@ -99,14 +127,14 @@ impl NodeParser for LiaMardownListParser {
             }
         }
         while pre_indentation > self.initial_indentation_depth {
-            append_closer(&mut inner_nodes);
+            append_closer(&mut inner_nodes, "itemize");
             pre_indentation -= 1;
         }
         Ok((
             vec![
                 Rc::new(TexEnvironment {
                     name: "itemize".to_string(),
-                    args: vec![],
+                    args: env_args(env_options),
                     children: node_list(&inner_nodes, 0, inner_nodes.len(), other_doc_locations)?,
                 }),
                 Rc::new(Text {
@@ -118,57 +146,164 @@ impl NodeParser for LiaMardownListParser {
     }
 }
 
-fn list_item(
+/// Marker of an item in a Markdown style list.
+pub struct ListItem<'a> {
+    pub env_name: &'a str,
+    /// `enumitem` options used if this item opens a nested list, e.g. `label=\alph*.`.
+    pub env_options: Option<String>,
+    /// Label of this item alone, e.g. a checkbox.
+    pub label: Option<String>,
+}
+
+impl<'a> ListItem<'a> {
+    pub fn new(env_name: &'a str) -> Self {
+        ListItem {
+            env_name,
+            env_options: None,
+            label: None,
+        }
+    }
+}
+
+pub fn list_item(
     item_count: &mut i32,
     indentation: usize,
     pre_indentation: &mut usize,
     loc: &Location,
     inner_nodes: &mut Vec<Token>,
-    i: usize,
-    tokens: &TokenList,
+    next_token: Option<&Token>,
+    item: &ListItem,
 ) -> Option<Result<(Vec<Rc<dyn Node>>, DocSection), String>> {
     *item_count += 1;
+    if let Err(e) = nest_to_indentation(
+        indentation,
+        pre_indentation,
+        loc,
+        inner_nodes,
+        item.env_name,
+        item.env_options.as_deref(),
+    ) {
+        return Some(Err(e));
+    }
+    inner_nodes.push(Token::TexCommand("\\item".to_string(), Location::default()));
+    if let Some(label) = &item.label {
+        inner_nodes.push(Token::Misc("[".to_string(), Location::default()));
+        inner_nodes.push(Token::Misc(label.clone(), Location::default()));
+        inner_nodes.push(Token::Misc("]".to_string(), Location::default()));
+    }
+    if let Some(next_token) = next_token {
+        if !matches!(next_token, Token::Whitespace(_)) {
+            inner_nodes.push(Token::Whitespace(" ".to_string()));
+        }
+    }
+    None
+}
+
+/// Opens or closes nested environments so the next item is at `indentation`.
+pub fn nest_to_indentation(
+    indentation: usize,
+    pre_indentation: &mut usize,
+    loc: &Location,
+    inner_nodes: &mut Vec<Token>,
+    env_name: &str,
+    env_options: Option<&str>,
+) -> Result<(), String> {
     if indentation > *pre_indentation {
         if indentation - *pre_indentation > 1 {
-            return Some(format_error_string(
-                "Indentation error. Nested item list was indented too far.".to_string(),
-                *loc,
+            return Err(format!(
+                "{} Indentation error. Nested item list was indented too far.",
+                loc.stringify()
             ));
         }
-        append_opener(inner_nodes);
+        append_opener(inner_nodes, env_name, env_options);
     } else if indentation < *pre_indentation {
         let diff = *pre_indentation - indentation;
         for _ in 0..diff {
-            append_closer(inner_nodes);
-        }
-    }
-    inner_nodes.push(Token::TexCommand("\\item".to_string(), Location::default()));
-    if i + 1 < tokens.len() {
-        if let Token::Whitespace(_) = &tokens[i + 1] {
-            {}
-        } else {
-            inner_nodes.push(Token::Whitespace(" ".to_string()));
+            append_closer(inner_nodes, env_name);
         }
     }
     *pre_indentation = indentation;
-    None
+    Ok(())
 }
 
-fn append_opener(inner_nodes: &mut Vec<Token>) {
+pub fn append_opener(inner_nodes: &mut Vec<Token>, env_name: &str, env_options: Option<&str>) {
     inner_nodes.push(Token::TexCommand(
         "\\begin".to_string(),
         Location::default(),
     ));
     inner_nodes.push(Token::Misc("{".to_string(), Location::default()));
-    inner_nodes.push(Token::Misc("itemize".to_string(), Location::default()));
+    inner_nodes.push(Token::Misc(env_name.to_string(), Location::default()));
     inner_nodes.push(Token::Misc("}".to_string(), Location::default()));
+    if let Some(options) = env_options {
+        inner_nodes.push(Token::Misc("[".to_string(), Location::default()));
+        inner_nodes.push(Token::Misc(options.to_string(), Location::default()));
+        inner_nodes.push(Token::Misc("]".to_string(), Location::default()));
+    }
     inner_nodes.push(Token::Newline);
 }
 
-fn append_closer(inner_nodes: &mut Vec<Token>) {
+pub fn append_closer(inner_nodes: &mut Vec<Token>, env_name: &str) {
     inner_nodes.push(Token::TexCommand("\\end".to_string(), Location::default()));
     inner_nodes.push(Token::Misc("{".to_string(), Location::default()));
-    inner_nodes.push(Token::Misc("itemize".to_string(), Location::default()));
+    inner_nodes.push(Token::Misc(env_name.to_string(), Location::default()));
     inner_nodes.push(Token::Misc("}".to_string(), Location::default()));
     inner_nodes.push(Token::Newline);
 }
+
+pub fn env_args(env_options: Option<String>) -> Vec<Arg> {
+    match env_options {
+        Some(options) => vec![Arg {
+            arg: vec![Rc::new(Text { text: options })],
+            arg_type: ArgType::Square,
+        }],
+        None => vec![],
+    }
+}
+
+/// A custom bullet `*(marker)`, returns the marker and the position of `)`.
+pub fn custom_marker(tokens: TokenList, cursor: usize) -> Option<(String, usize)> {
+    if tokens.get(cursor + 1)?.stringify() != "(" {
+        return None;
+    }
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(cursor + 1) {
+        match token.stringify().as_str() {
+            "(" => depth += 1,
+            ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    let marker = untokenise(&tokens[cursor + 2..i]);
+                    return if marker.trim().is_empty() {
+                        None
+                    } else {
+                        Some((marker.trim().to_string(), i))
+                    };
+                }
+            }
+            "\n" => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// A task item `* [ ]` or `* [x]`, returns whether it is checked and the position of `]`.
+fn task_marker(tokens: TokenList, cursor: usize) -> Option<(bool, usize)> {
+    let mut i = cursor + 1;
+    if let Some(Token::Whitespace(_)) = tokens.get(i) {
+        i += 1;
+    }
+    if tokens.get(i)?.stringify() != "[" {
+        return None;
+    }
+    let (checked, close) = match tokens.get(i + 1)? {
+        Token::Whitespace(w) if w == " " => (false, i + 2),
+        Token::Misc(t, _) if t == "]" => (false, i + 1),
+        Token::Misc(t, _) if t == "x" || t == "X" => (true, i + 2),
+        _ => return None,
+    };
+    if tokens.get(close)?.stringify() != "]" {
+        return None;
+    }
+    Some((checked, close))
+}
@@ -0,0 +1,252 @@
+use std::rc::Rc;
+
+use crate::at_expression::string_to_typed_value;
+use crate::bracket_depth::BrackDepths;
+use crate::document::{DocSection, Node, NodeList, TexEnvironment};
+use crate::parse::{node_list, CompilerGlobals, IndentationType, NodeParser, ParseResult};
+use crate::parser_modules::variables::var_definition::LiaVarName;
+use crate::parser_modules::variables::{dedent, substitute_args};
+use crate::token::*;
+use crate::tokenize::TokenList;
+use crate::typed_value::TypedValue;
+use crate::utils::{delta_bracket_depth, untokenise};
+
+/// Environments of lists, items from consecutive iterations are put in a single list.
+static LISTS: [&str; 3] = ["itemize", "enumerate", "description"];
+
+/// Loops running more often than this are most likely a mistake.
+static MAX_ITERATIONS: usize = 1000;
+
+/// `for @i in 1..10 { ... }` and `for @item in [a, b, c] { ... }`, expanded at compile time.
+#[derive(Default)]
+pub struct LiaForLoop {
+    curly_depth: i32,
+}
+
+#[allow(unused)]
+impl NodeParser for LiaForLoop {
+    fn is_opener(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        identation: i32,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> bool {
+        self.curly_depth = -1;
+        if !matches!(&tokens[cursor], Token::Misc(k, _) if k == "for") {
+            return false;
+        }
+        let Some(variable) = next_meaningful(tokens, cursor + 1) else {
+            return false;
+        };
+        let Some(keyword) = next_meaningful(tokens, variable + 1) else {
+            return false;
+        };
+        // Prose like "waiting for @name in line" has no body on the same line.
+        matches!(tokens[variable], Token::LiaVariable(_, _))
+            && matches!(&tokens[keyword], Token::Misc(k, _) if k == "in")
+            && tokens[keyword..]
+                .iter()
+                .take_while(|t| !t.is_newline())
+                .any(|t| matches!(t, Token::Misc(t, _) if t == "{"))
+    }
+
+    fn is_closer(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        bracket_depths: &BrackDepths,
+        start_bracket_depths: &BrackDepths,
+    ) -> bool {
+        let token = &tokens[cursor];
+
+        if self.curly_depth == -1 {
+            self.curly_depth = bracket_depths.curly;
+        }
+        match token {
+            Token::Misc(t, _) => t == "}" && bracket_depths.curly == self.curly_depth,
+            _ => false,
+        }
+    }
+
+    fn parse(
+        &mut self,
+        tokens: &[Token],
+        range_start: usize,
+        range_end: usize,
+        indentation_type: Option<IndentationType>,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> ParseResult {
+        let tokens = &tokens[range_start..=range_end];
+        let location = tokens[0].get_location();
+        let variable = next_meaningful(tokens, 1).unwrap();
+        let keyword = next_meaningful(tokens, variable + 1).unwrap();
+        let name = tokens[variable].stringify()[1..].to_string();
+        let Some(open) = tokens
+            .iter()
+            .position(|t| matches!(t, Token::Misc(t, _) if t == "{"))
+        else {
+            return Err(format!(
+                "{} Expected the body of the loop in {{}}.",
+                location.stringify()
+            ));
+        };
+
+        let values = loop_values(&tokens[keyword + 1..open], location, other_doc_locations)?;
+
+        let body = &tokens[open + 1..tokens.len() - 1];
+        // A body on lines of its own is repeated line by line, otherwise within the line as is.
+        let is_block = body.iter().any(|t| t.is_newline());
+        let body = if is_block {
+            dedent(body)
+        } else {
+            body.to_vec()
+        };
+        let outermost = other_doc_locations.loop_iterations.is_none();
+        let nodes = expand(
+            &body,
+            &name,
+            values,
+            is_block,
+            location,
+            other_doc_locations,
+        );
+        if outermost {
+            other_doc_locations.loop_iterations = None;
+        }
+        Ok((nodes?, DocSection::Document))
+    }
+}
+
+/// Repeats the body for every value, counting the iterations of nested loops too.
+fn expand(
+    body: TokenList,
+    name: &str,
+    values: Vec<Vec<Token>>,
+    is_block: bool,
+    location: Location,
+    other_doc_locations: &mut CompilerGlobals,
+) -> Result<NodeList, String> {
+    let params = [LiaVarName::Any(name.to_string())];
+    let mut nodes: NodeList = Vec::new();
+    for value in values {
+        let iterations = other_doc_locations.loop_iterations.unwrap_or(0) + 1;
+        if iterations > MAX_ITERATIONS {
+            return Err(format!(
+                "{} Loop would run more than the limit of {} times, counting nested loops.",
+                location.stringify(),
+                MAX_ITERATIONS
+            ));
+        }
+        other_doc_locations.loop_iterations = Some(iterations);
+        let mut iteration = substitute_args(body, &params, std::slice::from_ref(&value));
+        if is_block {
+            iteration.push(Token::Newline);
+        }
+        other_doc_locations
+            .constants
+            .push((name.to_string(), string_to_typed_value(untokenise(&value))?));
+        let iteration_nodes = node_list(&iteration, 0, iteration.len(), other_doc_locations);
+        other_doc_locations.constants.pop();
+        join_lists(&mut nodes, iteration_nodes?);
+    }
+    Ok(nodes)
+}
+
+/// Appends the nodes of an iteration, continuing a list the previous iteration ended with.
+fn join_lists(nodes: &mut NodeList, next: NodeList) {
+    let is_blank = |node: &Rc<dyn Node>| node.codegen().trim().is_empty();
+    let list = |node: Option<&Rc<dyn Node>>| {
+        node.and_then(|n| n.as_environment())
+            .filter(|e| LISTS.contains(&e.name.as_str()) && e.args.is_empty())
+            .map(|e| (e.name.clone(), e.children.clone()))
+    };
+    let last = nodes.iter().rposition(|n| !is_blank(n));
+    let first = next.iter().position(|n| !is_blank(n));
+    match (list(last.map(|i| &nodes[i])), list(first.map(|i| &next[i]))) {
+        (Some((name, mut children)), Some((next_name, next_children))) if name == next_name => {
+            children.extend(next_children.into_iter().skip_while(is_blank));
+            let (last, first) = (last.unwrap(), first.unwrap());
+            nodes.truncate(last);
+            nodes.push(Rc::new(TexEnvironment {
+                name,
+                args: vec![],
+                children,
+            }));
+            nodes.extend(next.into_iter().skip(first + 1));
+        }
+        _ => nodes.extend(next),
+    }
+}
+
+/// The next token that is not whitespace on the same line.
+pub fn next_meaningful(tokens: TokenList, start: usize) -> Option<usize> {
+    (start..tokens.len()).find(|i| !matches!(tokens[*i], Token::Whitespace(_)))
+}
+
+/// The values of `1..10`, both ends included, or of `[a, b, c]`.
+fn loop_values(
+    header: TokenList,
+    location: Location,
+    other_doc_locations: &CompilerGlobals,
+) -> Result<Vec<Vec<Token>>, String> {
+    let header = dedent(header);
+    let is =
+        |t: Option<&Token>, bracket: &str| matches!(t, Some(Token::Misc(t, _)) if t == bracket);
+    if is(header.first(), "[") && is(header.last(), "]") {
+        let mut items: Vec<Vec<Token>> = vec![Vec::new()];
+        let mut depth = BrackDepths::default();
+        for t in &header[1..header.len() - 1] {
+            depth += delta_bracket_depth(t);
+            match t {
+                Token::Misc(c, _) if c == "," && depth.is_zero() => items.push(Vec::new()),
+                _ => items.last_mut().unwrap().push(t.clone()),
+            }
+        }
+        return Ok(items
+            .iter()
+            .map(|item| dedent(item))
+            .filter(|item| !item.is_empty())
+            .collect());
+    }
+
+    let range = untokenise(&header);
+    let Some((start, end)) = range.split_once("..") else {
+        return Err(format!(
+            "{} Expected a range such as 1..10 or a list such as [a, b, c] after \"in\", found \"{}\".",
+            location.stringify(),
+            range
+        ));
+    };
+    let bound = |bound: &str| -> Result<i64, String> {
+        let bound = bound.trim();
+        let value = match bound.strip_prefix('@') {
+            Some(constant) => other_doc_locations.constant(constant).cloned(),
+            None => bound.parse::<f64>().ok().map(TypedValue::Number),
+        };
+        match value {
+            Some(TypedValue::Number(n)) if n.fract() == 0.0 => Ok(n as i64),
+            _ => Err(format!(
+                "{} Expected a whole number or a constant holding one in range \"{}\", found \"{}\".",
+                location.stringify(),
+                range,
+                bound
+            )),
+        }
+    };
+    let (start, end) = (bound(start)?, bound(end)?);
+    let count = start.abs_diff(end) as usize + 1;
+    if count > MAX_ITERATIONS {
+        return Err(format!(
+            "{} Loop over {} would run {} times, more than the limit of {}.",
+            location.stringify(),
+            range,
+            count,
+            MAX_ITERATIONS
+        ));
+    }
+    let step = if start <= end { 1 } else { -1 };
+    Ok((0..count as i64)
+        .map(|i| vec![Token::Misc((start + i * step).to_string(), location)])
+        .collect())
+}
@@ -1,11 +1,20 @@
+pub mod aligned_equation;
 pub mod bold_italic;
 pub mod comments;
+pub mod conditionals;
+pub mod description_list;
 pub mod enumerated_list;
 pub mod environments;
 pub mod equation;
+pub mod footnotes;
+pub mod horizontal_rule;
 pub mod imports;
 pub mod inline_code;
+pub mod inline_math;
+pub mod labels;
 pub mod list;
+pub mod loops;
+pub mod quote;
 pub mod section;
 pub mod tex_command;
 pub mod variables;
@@ -0,0 +1,110 @@
+use std::rc::Rc;
+
+use crate::bracket_depth::BrackDepths;
+use crate::document::{DocSection, TexEnvironment, Text};
+use crate::parse::{node_list, CompilerGlobals, IndentationType, NodeParser, ParseResult};
+use crate::parser_modules::list::{append_closer, append_opener};
+use crate::token::*;
+use crate::utils::{delta_bracket_depth, is_start_of_line};
+
+#[derive(Default)]
+pub struct LiaMarkdownQuoteParser {}
+
+impl NodeParser for LiaMarkdownQuoteParser {
+    fn is_opener(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        _identation: i32,
+        _other_doc_locations: &mut CompilerGlobals,
+    ) -> bool {
+        quote_depth(tokens, cursor).is_some() && is_start_of_line(tokens, cursor)
+    }
+
+    fn is_closer(
+        &mut self,
+        tokens: &[Token],
+        cursor: usize,
+        bracket_depths: &BrackDepths,
+        start_bracket_depths: &BrackDepths,
+    ) -> bool {
+        let mut next = cursor + 1;
+        while let Some(Token::Whitespace(_)) = tokens.get(next) {
+            next += 1;
+        }
+
+        bracket_depths.curly == start_bracket_depths.curly
+            && tokens[cursor].is_newline()
+            && quote_depth(tokens, next).is_none()
+    }
+
+    fn parse(
+        &mut self,
+        tokens: &[Token],
+        range_start: usize,
+        range_end: usize,
+        _indentation_type: Option<IndentationType>,
+        other_doc_locations: &mut CompilerGlobals,
+    ) -> ParseResult {
+        let tokens = &tokens[range_start..=range_end];
+
+        let mut pre_depth = 1;
+        let mut inner_nodes: Vec<Token> = vec![Token::Newline];
+        let mut brack_depth = BrackDepths::default();
+        let mut i = 0;
+        while i < tokens.len() {
+            brack_depth += delta_bracket_depth(&tokens[i]);
+            match quote_depth(tokens, i) {
+                Some(depth) if brack_depth.curly == 0 && is_start_of_line(tokens, i) => {
+                    for _ in pre_depth..depth {
+                        append_opener(&mut inner_nodes, "quote", None);
+                    }
+                    for _ in depth..pre_depth {
+                        append_closer(&mut inner_nodes, "quote");
+                    }
+                    pre_depth = depth;
+                    // Drop the space after the marker.
+                    i += 1;
+                }
+                _ => {
+                    if let Token::Whitespace(_) = tokens[i] {
+                        if is_start_of_line(tokens, i) {
+                            i += 1;
+                            continue;
+                        }
+                    }
+                    inner_nodes.push(tokens[i].clone());
+                }
+            }
+            i += 1;
+        }
+        for _ in 1..pre_depth {
+            append_closer(&mut inner_nodes, "quote");
+        }
+        Ok((
+            vec![
+                Rc::new(TexEnvironment {
+                    name: "quote".to_string(),
+                    args: vec![],
+                    children: node_list(&inner_nodes, 0, inner_nodes.len(), other_doc_locations)?,
+                }),
+                Rc::new(Text {
+                    text: "\n".to_string(),
+                }),
+            ],
+            DocSection::Document,
+        ))
+    }
+}
+
+/// The nesting depth of a `>`, `>>`, ... quote marker, which must be followed by a space.
+fn quote_depth(tokens: &[Token], i: usize) -> Option<usize> {
+    match (tokens.get(i)?, tokens.get(i + 1)?) {
+        (Token::Misc(t, _), Token::Whitespace(_))
+            if !t.is_empty() && t.chars().all(|c| c == '>') =>
+        {
+            Some(t.len())
+        }
+        _ => None,
+    }
+}
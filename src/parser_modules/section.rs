@@ -3,6 +3,7 @@ use std::rc::Rc;
 use crate::bracket_depth::BrackDepths;
 use crate::document::*;
 use crate::parse::*;
+use crate::parser_modules::labels::{label_command, parse_label_name, register_label};
 use crate::token::*;
 use crate::tokenize::TokenList;
 use crate::utils::format_error_string;
@@ -72,36 +73,81 @@ impl NodeParser for LiaMarkDownSections {
             }
         }
 
-        Ok((
-            vec![
-                Rc::new(TexCommand {
-                    command,
-                    args: vec![Arg {
-                        arg: { rest_of_line(&tokens, other_doc_locations)? },
-                        arg_type: ArgType::Curly,
-                    }],
-                }),
-                Rc::new(Text {
-                    text: "\n".to_string(),
-                }),
-            ],
-            DocSection::Document,
-        ))
+        let (title_end, label) = match find_trailing_label(tokens)? {
+            Some((label_start, label)) => {
+                register_label(
+                    label.clone(),
+                    LabelKind::Section,
+                    tokens[0].get_location(),
+                    other_doc_locations,
+                )?;
+                (label_start, Some(label))
+            }
+            None => (tokens.len() - 1, None),
+        };
+
+        let mut nodes = vec![Rc::new(TexCommand {
+            command,
+            args: vec![Arg {
+                arg: { rest_of_line(&tokens, title_end, other_doc_locations)? },
+                arg_type: ArgType::Curly,
+            }],
+        }) as Rc<dyn Node>];
+        if let Some(label) = label {
+            nodes.push(label_command(label));
+        }
+        nodes.push(Rc::new(Text {
+            text: "\n".to_string(),
+        }));
+
+        Ok((nodes, DocSection::Document))
+    }
+}
+
+/// Finds a `{#label}` at the end of a section line. Returns the position the
+/// title ends at, excluding whitespace, and the label.
+fn find_trailing_label(tokens: TokenList) -> Result<Option<(usize, String)>, String> {
+    let mut end = tokens.len();
+    while end > 0 {
+        match &tokens[end - 1] {
+            Token::Whitespace(_) | Token::Newline => end -= 1,
+            _ => break,
+        }
+    }
+    if end == 0 || tokens[end - 1].stringify() != "}" {
+        return Ok(None);
+    }
+    let close = end - 1;
+    let mut open = close;
+    while open > 0 && tokens[open].stringify() != "{" {
+        open -= 1;
+    }
+    if tokens[open].stringify() != "{" || tokens[open + 1].stringify() != "#" {
+        return Ok(None);
+    }
+    let label = parse_label_name(&tokens[open + 2..close], tokens[open].get_location())?;
+    while open > 1 {
+        if let Token::Whitespace(_) = tokens[open - 1] {
+            open -= 1;
+        } else {
+            break;
+        }
     }
+    Ok(Some((open, label)))
 }
 
 fn rest_of_line(
     tokens: &TokenList,
+    end: usize,
     other_doc_locations: &mut CompilerGlobals,
 ) -> Result<NodeList, String> {
-    let len = tokens.len();
     let mut start = 1;
-    while start < len {
+    while start < end {
         if let Token::Whitespace(_) = tokens[start] {
             start += 1;
         } else {
             break;
         }
     }
-    node_list(tokens, start, len - 1, other_doc_locations)
+    node_list(tokens, start, end, other_doc_locations)
 }
@@ -1,10 +1,11 @@
 use std::rc::Rc;
 
 use crate::bracket_depth::BrackDepths;
-use crate::document::{DocSection, Node, TexCommand, TexEnvironment, Text};
+use crate::document::{DocSection, LabelKind, Node, TexCommand, TexEnvironment, Text};
 use crate::parse::{node_list, CompilerGlobals, IndentationType, NodeParser, ParseResult};
+use crate::parser_modules::labels::register_label;
 use crate::token::*;
-use crate::utils::parse_args;
+use crate::utils::{delta_bracket_depth, parse_args};
 
 #[derive(Default, PartialEq, Debug)]
 enum EnvParsingState {
@@ -95,10 +96,11 @@ impl NodeParser for TexCommandParser {
                     && bracket_depths.square == 0
                     && match next_token {
                         Token::Misc(t, _) => t != "{" && t != "[",
-                        Token::Newline => {
+                        // Consume trailing newline, unless the range ends here, e.g. `@f(\alpha)`.
+                        Token::Newline if cursor + 1 < tokens.len() => {
                             self.next = true;
                             false
-                        } // Consume trailing newline
+                        }
                         _ => true,
                     }
                     && match next_token_no_white_space {
@@ -196,9 +198,15 @@ impl TexCommandParser {
         } else {
             4
         };
+        let args_end = if LIST_ENVIRONMENTS.contains(&self.env_name.as_str()) {
+            env_options_end(tokens, edge_size)
+        } else {
+            edge_size
+        };
+        let args = parse_args(&tokens, edge_size, args_end, other_doc_locations)?;
         let children = node_list(
             tokens,
-            edge_size,
+            args_end,
             tokens.len() - edge_size,
             other_doc_locations,
         )?;
@@ -209,7 +217,7 @@ impl TexCommandParser {
         Ok((
             vec![Rc::new(TexEnvironment {
                 name: self.env_name.clone(),
-                args: vec![],
+                args,
                 children,
             })],
             DocSection::Document,
@@ -251,10 +259,25 @@ impl TexCommandParser {
             _ => DocSection::Document,
         };
 
-        let mut v = vec![Rc::new(TexCommand {
-            command,
-            args: parse_args(&tokens, 1, tokens.len(), other_doc_locations)?,
-        }) as Rc<dyn Node>];
+        let args = parse_args(&tokens, 1, tokens.len(), other_doc_locations)?;
+        if command == "label" && args.len() == 1 {
+            let label = args[0]
+                .arg
+                .iter()
+                .map(|node| node.codegen())
+                .collect::<String>();
+            // Labels built from macro parameters can't be known until TeX expands them.
+            if !label.contains('#') {
+                register_label(
+                    label,
+                    LabelKind::Other,
+                    tokens[0].get_location(),
+                    other_doc_locations,
+                )?;
+            }
+        }
+
+        let mut v = vec![Rc::new(TexCommand { command, args }) as Rc<dyn Node>];
         if let Token::Newline = tokens.last().unwrap() {
             v.push(Rc::new(Text {
                 text: "\n".to_string(),
@@ -264,3 +287,23 @@ impl TexCommandParser {
         Ok((v, section))
     }
 }
+
+/// Lists whose `enumitem` options, e.g. `\begin{enumerate}[label=\alph*.]`, are kept on the
+/// `\begin` line. Nested lists with a label style are written this way.
+static LIST_ENVIRONMENTS: [&str; 3] = ["itemize", "enumerate", "description"];
+
+/// Finds the end of the `[...]` options directly following `\begin{name}`.
+fn env_options_end(tokens: &[Token], start: usize) -> usize {
+    if !matches!(tokens.get(start), Some(Token::Misc(t, _)) if t == "[") {
+        return start;
+    }
+    let mut bracket_depths = BrackDepths::default();
+    let close = tokens.iter().skip(start).position(|token| {
+        bracket_depths += delta_bracket_depth(token);
+        bracket_depths.square == 0
+    });
+    match close {
+        Some(close) => start + close + 1,
+        None => start,
+    }
+}
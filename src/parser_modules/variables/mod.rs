@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::rc::Rc;
 
 use crate::{
@@ -13,6 +14,11 @@ use crate::{
 pub mod var_definition;
 use crate::ast::Ast;
 use crate::at_expression::*;
+use crate::colour::Colour;
+use crate::data;
+use crate::latex_commands::is_latex_command;
+use crate::number_format::{format_value, NumberFormat};
+use crate::size::Size;
 use crate::typed_value::TypedValue;
 use var_definition::*;
 
@@ -29,8 +35,15 @@ pub struct LiaVariableParser {
 pub struct Function {
     name: String,
     args: Vec<LiaVarName>,
+    /// Default values of the arguments, `(title, width: Size = 0.8\textwidth)`.
+    defaults: Vec<Option<Vec<Token>>>,
+    /// Body of a function that is expanded at compile time, declared with `inline`.
+    body: Option<Vec<Token>>,
 }
 
+/// Inline functions calling themselves would otherwise expand forever.
+static MAX_INLINE_DEPTH: usize = 64;
+
 #[derive(Default)]
 enum StatmentType {
     #[default]
@@ -69,7 +82,7 @@ impl NodeParser for LiaVariableParser {
         let token = &tokens[cursor];
         let next_token_no_white_space =
             &crate::utils::move_past_whitespace(tokens, cursor + 1).unwrap_or(Token::Newline);
-        let next_token = &tokens[cursor + 1];
+        let next_token = &tokens.get(cursor + 1).cloned().unwrap_or(Token::Newline);
 
         if self.curly_depth == -1 {
             self.curly_depth = bracket_depths.curly;
@@ -138,9 +151,41 @@ impl NodeParser for LiaVariableParser {
     ) -> ParseResult {
         let tokens = &tokens[range_start..=range_end];
 
+        if let (Token::LiaVariable(var, _), Some(StatmentType::Call)) =
+            (&tokens[0], &self.statement_type)
+        {
+            if var == "@" {
+                let (value, format) = evaluate_at_expression(tokens, other_doc_locations)?;
+                return Ok((
+                    vec![computed_text(
+                        &value,
+                        format.as_ref(),
+                        tokens[0].get_location(),
+                        other_doc_locations,
+                    )?],
+                    DocSection::Document,
+                ));
+            }
+        }
+
+        let location = tokens[0].get_location();
+        let (command, renew) = match &tokens[0] {
+            Token::LiaVariable(command, _) => match command.strip_prefix("@!") {
+                Some(command) => (command, true),
+                None => (&command[1..], false),
+            },
+            _ => panic!("Should not be here."),
+        };
+        if renew && !matches!(self.statement_type, Some(StatmentType::Assign)) {
+            return Err(format!(
+                "{} \"@!{}\" can only be used to redefine a command, e.g. \"@!{} = ...\".",
+                location.stringify(),
+                command,
+                command
+            ));
+        }
         let command = match &tokens[0] {
-            Token::LiaVariable(command, loc) => {
-                    let command = &command[1..];
+            Token::LiaVariable(_, loc) => {
                     if command.is_empty() {
                         return Err(
                             format!{"{} Invalid variable name \"{}\". Aborted.", loc.stringify(), command}
@@ -160,26 +205,78 @@ impl NodeParser for LiaVariableParser {
                 })],
                 DocSection::Document,
             )),
-            Some(StatmentType::Call) => Ok((
-                vec![Rc::new(TexCommand {
-                    command: command.clone(),
-                    args: split_call_args(
-                        &tokens,
-                        2,
-                        tokens.len() - 1,
-                        other_doc_locations,
-                        is_defined_function(command, other_doc_locations.fucntions.clone()),
-                    )?,
-                })],
-                DocSection::Document,
-            )),
+            Some(StatmentType::Call) => {
+                let is_user_function =
+                    is_defined_function(command.clone(), other_doc_locations.fucntions.clone())
+                        .is_some();
+                if command == "table" && !is_user_function {
+                    return Ok((
+                        vec![data_table(tokens, location, other_doc_locations)?],
+                        DocSection::Document,
+                    ));
+                }
+                other_doc_locations
+                    .function_calls
+                    .push((command.clone(), location));
+                if let Some(function) =
+                    is_defined_function(command.clone(), other_doc_locations.fucntions.clone())
+                {
+                    if function.body.is_some() {
+                        return Ok((
+                            expand_inline_call(tokens, &function, other_doc_locations)?,
+                            DocSection::Document,
+                        ));
+                    }
+                }
+                Ok((
+                    vec![Rc::new(TexCommand {
+                        command: command.clone(),
+                        args: split_call_args(
+                            &tokens,
+                            2,
+                            tokens.len() - 1,
+                            other_doc_locations,
+                            is_defined_function(command, other_doc_locations.fucntions.clone()),
+                        )?,
+                    })],
+                    DocSection::Document,
+                ))
+            }
             Some(StatmentType::Assign) => {
                 if command == "LIAVERSION" {
                     other_doc_locations.feature_status_list = get_status_list(
                         &strip_all_whitespace(untokenise(tokens).split('=').next_back().unwrap()),
                     )?;
                     Ok((vec![], DocSection::Document))
+                } else if command == "LIAASCII" {
+                    other_doc_locations.ascii_text = bool_setting(&command, tokens)?;
+                    Ok((vec![], DocSection::Document))
+                } else if command == "LIAINLINE" {
+                    other_doc_locations.inline_functions = bool_setting(&command, tokens)?;
+                    Ok((vec![], DocSection::Document))
+                } else if command == "LIANUMBERFORMAT" {
+                    let value = untokenise(tokens)
+                        .split_once('=')
+                        .map(|(_, v)| strip_all_whitespace(v))
+                        .unwrap_or_default();
+                    other_doc_locations.number_format =
+                        Some(NumberFormat::parse(&value).map_err(|e| {
+                            format!("{} {}", tokens[0].get_location().stringify(), e)
+                        })?);
+                    Ok((vec![], DocSection::Document))
+                } else if let Some(path) = load_call(tokens) {
+                    declare(&command, location, renew, false, other_doc_locations)?;
+                    let value = load_data(&path, location, other_doc_locations)?;
+                    other_doc_locations.constants.push((command, value));
+                    Ok((vec![], DocSection::Document))
+                } else if let Some(function) =
+                    inline_function_declaration(&command, tokens, other_doc_locations)?
+                {
+                    declare(&command, location, renew, false, other_doc_locations)?;
+                    other_doc_locations.fucntions.push(function);
+                    Ok((vec![], DocSection::Document))
                 } else {
+                    let renew = declare(&command, location, renew, true, other_doc_locations)?;
                     Ok((
                         vec![
                             parse_var_declaration(
@@ -188,6 +285,7 @@ impl NodeParser for LiaVariableParser {
                                 self.terminated_by_newline,
                                 other_doc_locations,
                                 self.trailing_whitespace,
+                                renew,
                             )?,
                             Rc::new(Text {
                                 text: "\n".to_string(),
@@ -204,8 +302,503 @@ impl NodeParser for LiaVariableParser {
     }
 }
 
+/// The path in `@data = load("results.csv")`.
+fn load_call(tokens: TokenList) -> Option<String> {
+    let equals = find_nothing_token(&tokens, "=")?;
+    let value = dedent(&tokens[equals + 1..]);
+    match value.as_slice() {
+        [Token::Misc(load, _), Token::Misc(open, _), path @ .., Token::Misc(close, _)]
+            if load == "load" && open == "(" && close == ")" =>
+        {
+            Some(untokenise(path).trim().trim_matches('"').to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Loads a data file relative to the document, which is then rebuilt when it changes.
+fn load_data(
+    path: &str,
+    location: Location,
+    other_doc_locations: &mut CompilerGlobals,
+) -> Result<TypedValue, String> {
+    let path = Path::new(&other_doc_locations.job.input_path)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(path);
+    other_doc_locations
+        .dependencies
+        .push(path.to_string_lossy().to_string());
+    data::load(&path).map_err(|e| format!("{} {}", location.stringify(), e))
+}
+
+/// `@table(data, columns = [time, voltage], headers = [Time (s), Voltage (V)], format = .2f)`.
+fn data_table(
+    tokens: TokenList,
+    location: Location,
+    other_doc_locations: &mut CompilerGlobals,
+) -> Result<Rc<dyn Node>, String> {
+    let error = |e: String| format!("{} {}", location.stringify(), e);
+    let args = split_inline_args(&tokens[2..tokens.len() - 1]);
+    let Some(rows) = args.first() else {
+        return Err(error(
+            "Expected the data to put in the table, e.g. @table(data).".to_string(),
+        ));
+    };
+    let rows = untokenise(rows);
+    let rows = rows.trim_start_matches('@');
+    let rows = data::lookup(rows, other_doc_locations)
+        .ok_or_else(|| error(format!("\"{}\" is not data loaded with load().", rows)))?
+        .map_err(error)?;
+
+    let (mut columns, mut headers, mut format) = (None, None, None);
+    for arg in &args[1..] {
+        let equals = find_nothing_token(&arg.as_slice(), "=").ok_or_else(|| {
+            error(
+                "Expected columns = [...], headers = [...] or format = ... after the data."
+                    .to_string(),
+            )
+        })?;
+        let name = strip_all_whitespace(&untokenise(&arg[..equals]));
+        let value = dedent(&arg[equals + 1..]);
+        let list = || -> Result<Vec<String>, String> {
+            match value.as_slice() {
+                [Token::Misc(open, _), items @ .., Token::Misc(close, _)]
+                    if open == "[" && close == "]" =>
+                {
+                    Ok(split_inline_args(items)
+                        .iter()
+                        .map(|i| untokenise(i))
+                        .collect())
+                }
+                _ => Err(error(format!(
+                    "Expected a list such as [a, b] for {}.",
+                    name
+                ))),
+            }
+        };
+        let given_twice = match name.as_str() {
+            "columns" => columns.replace(list()?).is_some(),
+            "headers" => headers.replace(list()?).is_some(),
+            "format" => format
+                .replace(NumberFormat::parse(&untokenise(&value)).map_err(error)?)
+                .is_some(),
+            _ => {
+                return Err(error(format!(
+                    "Unknown argument \"{}\" of table, expected columns, headers or format.",
+                    name
+                )))
+            }
+        };
+        if given_twice {
+            return Err(error(format!(
+                "Argument \"{}\" of table was given more than once.",
+                name
+            )));
+        }
+    }
+    let format = format.or(other_doc_locations.number_format.clone());
+    if format.as_ref().is_some_and(|f| f.is_siunitx()) {
+        other_doc_locations.require_package("siunitx");
+    }
+    data::table(&rows, columns, headers, format.as_ref()).map_err(error)
+}
+
+/// Remembers where `name` is declared. Declaring it again is an error unless it is `@!name`.
+fn declare(
+    name: &str,
+    location: Location,
+    renew: bool,
+    emits_macro: bool,
+    other_doc_locations: &mut CompilerGlobals,
+) -> Result<bool, String> {
+    let previous = other_doc_locations
+        .declarations
+        .iter()
+        .find(|(n, _)| n == name);
+    let renew = match previous {
+        Some((_, previous)) if !renew => {
+            return Err(format!(
+                "{} \"@{}\" is already defined at {}. Use \"@!{} = ...\" to redefine it.",
+                location.stringify(),
+                name,
+                previous.stringify(),
+                name
+            ))
+        }
+        None if !renew && emits_macro && is_latex_command(name) => {
+            other_doc_locations.warn(
+                location,
+                format!(
+                    "\"@{}\" has the same name as the LaTeX command \\{}. Use \"@!{} = ...\" to redefine it.",
+                    name, name, name
+                ),
+            );
+            false
+        }
+        // `\renewcommand` fails on a command that doesn't exist yet.
+        None if renew && !is_latex_command(name) => {
+            other_doc_locations.warn(
+                location,
+                format!(
+                    "\"@!{}\" redefines \\{}, which is not defined, so \\newcommand is used instead.",
+                    name, name
+                ),
+            );
+            false
+        }
+        _ => renew,
+    };
+    other_doc_locations
+        .declarations
+        .push((name.to_string(), location));
+    Ok(renew)
+}
+
+/// Warns about `@name(...)` calls of names that are neither declared nor LaTeX commands.
+pub fn check_function_calls(other_doc_locations: &CompilerGlobals) {
+    for (name, location) in &other_doc_locations.function_calls {
+        let declared = other_doc_locations
+            .declarations
+            .iter()
+            .any(|(n, _)| n == name);
+        if !declared && !is_latex_command(name) {
+            other_doc_locations.warn(
+                *location,
+                format!(
+                    "Call to \"@{}\", which is not declared in this document.",
+                    name
+                ),
+            );
+        }
+    }
+}
+
+/// `@LIAASCII = true`, only `true` and `false` are accepted.
+fn bool_setting(command: &str, tokens: TokenList) -> Result<bool, String> {
+    let value = strip_all_whitespace(untokenise(tokens).split('=').next_back().unwrap());
+    match value.as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!(
+            "{} Expected true or false for @{}, found \"{}\".",
+            tokens[0].get_location().stringify(),
+            command,
+            value
+        )),
+    }
+}
+
+/// `@f = inline (a, b) => {...}`, or any function after `@LIAINLINE = true`.
+fn inline_function_declaration(
+    command: &str,
+    tokens: TokenList,
+    other_doc_locations: &CompilerGlobals,
+) -> Result<Option<Function>, String> {
+    let Some(arrow_pos) = find_nothing_token(&tokens, "=>") else {
+        return Ok(None);
+    };
+    let (lhs, rhs) = tokens.split_at(arrow_pos);
+    let keyword = lhs
+        .iter()
+        .position(|t| matches!(t, Token::Misc(t, _) if t == "inline"));
+    if keyword.is_none() && !other_doc_locations.inline_functions {
+        return Ok(None);
+    }
+    let lhs: Vec<Token> = lhs
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != keyword)
+        .map(|(_, t)| t.clone())
+        .collect();
+    let is_brace = |t: &Token, brace: &str| matches!(t, Token::Misc(t, _) if t == brace);
+    let (Some(open), Some(close)) = (
+        rhs.iter().position(|t| is_brace(t, "{")),
+        rhs.iter().rposition(|t| is_brace(t, "}")),
+    ) else {
+        return Err(format!(
+            "{} Expected the body of inline function {} in {{}}.",
+            tokens[0].get_location().stringify(),
+            command
+        ));
+    };
+    let (args, defaults) = parse_fn_declaration_lhs(&lhs)?.into_iter().unzip();
+    Ok(Some(Function {
+        name: command.to_string(),
+        args,
+        defaults,
+        body: Some(dedent(&rhs[open + 1..close])),
+    }))
+}
+
+/// Strips the blank lines around a body and the indentation its lines share.
+pub fn dedent(tokens: TokenList) -> Vec<Token> {
+    let meaningful = |t: &Token| !matches!(t, Token::Whitespace(_) | Token::Newline);
+    let (Some(first), Some(last)) = (
+        tokens.iter().position(meaningful),
+        tokens.iter().rposition(meaningful),
+    ) else {
+        return Vec::new();
+    };
+    let start = tokens[..first]
+        .iter()
+        .rposition(|t| t.is_newline())
+        .map_or(first, |i| i + 1);
+    let tokens = &tokens[start..=last];
+    let line_start = |i: usize| i == 0 || tokens[i - 1].is_newline();
+    // A body starting right after `{` has no indentation to strip.
+    let indent = match tokens[0] {
+        Token::Whitespace(_) => tokens
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| match t {
+                Token::Whitespace(w) if line_start(i) => Some(w.len()),
+                _ => None,
+            })
+            .min()
+            .unwrap_or(0),
+        _ => 0,
+    };
+    tokens
+        .iter()
+        .enumerate()
+        .filter_map(|(i, t)| match t {
+            Token::Whitespace(w) if line_start(i) => {
+                let w = &w[indent.min(w.len())..];
+                (!w.is_empty()).then(|| Token::Whitespace(w.to_string()))
+            }
+            _ => Some(t.clone()),
+        })
+        .collect()
+}
+
+/// Expands `@f(a, b)` by substituting the arguments into the body and parsing it.
+fn expand_inline_call(
+    tokens: TokenList,
+    function: &Function,
+    other_doc_locations: &mut CompilerGlobals,
+) -> Result<NodeList, String> {
+    let location = tokens[0].get_location();
+    let args = resolve_call_args(
+        location,
+        function,
+        split_inline_args(&tokens[2..tokens.len() - 1]),
+    )?
+    .iter()
+    .map(|arg| dedent(arg))
+    .collect::<Vec<Vec<Token>>>();
+    if other_doc_locations.inline_depth >= MAX_INLINE_DEPTH {
+        return Err(format!(
+            "{} Inline function {} is nested more than {} calls deep. Aborted.",
+            location.stringify(),
+            function.name,
+            MAX_INLINE_DEPTH
+        ));
+    }
+    let constants_len = other_doc_locations.constants.len();
+    for (i, (param, arg)) in function.args.iter().zip(&args).enumerate() {
+        let mut value = string_to_typed_value(untokenise(arg))?;
+        if let (LiaVarName::Colour(_), TypedValue::String(name)) = (param, &value) {
+            if let Some(colour) = Colour::named(name, &other_doc_locations.colours) {
+                value = TypedValue::Colour(colour);
+            }
+        }
+        if let (LiaVarName::Size(_), TypedValue::String(text)) = (param, &value) {
+            if let Some(size) = Size::parse(text) {
+                value = TypedValue::Size(size);
+            }
+        }
+        if !value.matches_declaration_type(param) {
+            other_doc_locations.constants.truncate(constants_len);
+            return Err(format!(
+                "{} Recieved mismatched types for argument {} of function {}.",
+                location.stringify(),
+                i + 1,
+                function.name
+            ));
+        }
+        other_doc_locations
+            .constants
+            .push((param_name(param).to_string(), value));
+    }
+
+    let mut body = substitute_args(
+        function.body.as_deref().unwrap_or_default(),
+        &function.args,
+        &args,
+    );
+    // Blocks such as lists are only closed by the end of their line.
+    if body.iter().any(|t| t.is_newline()) {
+        body.push(Token::Newline);
+    }
+    other_doc_locations.inline_depth += 1;
+    let nodes = node_list(&body, 0, body.len(), other_doc_locations);
+    other_doc_locations.inline_depth -= 1;
+    other_doc_locations.constants.truncate(constants_len);
+    nodes
+}
+
+fn param_name(param: &LiaVarName) -> &str {
+    match param {
+        LiaVarName::Number(n)
+        | LiaVarName::String(n)
+        | LiaVarName::Size(n)
+        | LiaVarName::Colour(n)
+        | LiaVarName::Bool(n)
+        | LiaVarName::Any(n) => n,
+        LiaVarName::Lamda(_) => "",
+    }
+}
+
+/// Splits call arguments on commas that are not nested in brackets.
+fn split_inline_args(tokens: TokenList) -> Vec<Vec<Token>> {
+    let mut args: Vec<Vec<Token>> = vec![Vec::new()];
+    let mut depth = BrackDepths::default();
+    for t in tokens {
+        match t {
+            Token::Misc(c, _) if c == "," && depth.is_zero() => args.push(Vec::new()),
+            _ => {
+                depth += delta_bracket_depth(t);
+                args.last_mut().unwrap().push(t.clone());
+            }
+        }
+    }
+    let trimmed: Vec<Vec<Token>> = args.iter().map(|arg| dedent(arg)).collect();
+    if trimmed.len() == 1 && trimmed[0].is_empty() {
+        return Vec::new();
+    }
+    trimmed
+}
+
+/// Replaces `@a` outside of `@()` expressions with its argument. Inside them it reads its value.
+pub fn substitute_args(body: TokenList, params: &[LiaVarName], args: &[Vec<Token>]) -> Vec<Token> {
+    let mut ret: Vec<Token> = Vec::new();
+    let mut depth = BrackDepths::default();
+    // Round bracket depth outside of the @() expression being read.
+    let mut at_expression: Option<i32> = None;
+    for t in body {
+        depth += delta_bracket_depth(t);
+        match t {
+            Token::LiaVariable(var, _) if var == "@" => {
+                if at_expression.is_none() {
+                    at_expression = Some(depth.round);
+                }
+                ret.push(t.clone());
+            }
+            Token::LiaVariable(var, _) if at_expression.is_none() => {
+                match params.iter().position(|a| a.matches_name(&var[1..])) {
+                    Some(k) => ret.extend(args[k].iter().cloned()),
+                    None => ret.push(t.clone()),
+                }
+            }
+            _ => {
+                if at_expression == Some(depth.round) && t.stringify() == ")" {
+                    at_expression = None;
+                }
+                ret.push(t.clone());
+            }
+        }
+    }
+    ret
+}
+
+/// Puts named arguments in place and fills in defaults, `@fig(cat.png, width = 5cm)`.
+/// With fewer positional arguments than free slots, the leftmost defaults are used first.
+fn resolve_call_args(
+    location: Location,
+    function: &Function,
+    args: Vec<Vec<Token>>,
+) -> Result<Vec<Vec<Token>>, String> {
+    let params: Vec<&LiaVarName> = function
+        .args
+        .iter()
+        .filter(|a| !matches!(a, LiaVarName::Lamda(_)))
+        .collect();
+    if args.len() > params.len() {
+        return Err(format!(
+            "{} Function {} takes {} arguments, but {} were given.",
+            location.stringify(),
+            function.name,
+            params.len(),
+            args.len()
+        ));
+    }
+    let mut resolved: Vec<Option<Vec<Token>>> = vec![None; params.len()];
+    let mut positional: Vec<Vec<Token>> = Vec::new();
+    for arg in args {
+        match named_argument(&arg, &params) {
+            Some((k, value)) => {
+                if resolved[k].replace(value).is_some() {
+                    return Err(format!(
+                        "{} Argument \"{}\" of function {} was given more than once.",
+                        location.stringify(),
+                        param_name(params[k]),
+                        function.name
+                    ));
+                }
+            }
+            None => positional.push(arg),
+        }
+    }
+    let free = resolved.iter().filter(|r| r.is_none()).count();
+    if positional.len() > free {
+        return Err(format!(
+            "{} Function {} takes {} arguments, but {} were given.",
+            location.stringify(),
+            function.name,
+            params.len(),
+            positional.len() + params.len() - free
+        ));
+    }
+    let mut skip = free - positional.len();
+    let mut positional = positional.into_iter();
+    for (k, slot) in resolved.iter_mut().enumerate() {
+        if slot.is_some() {
+            continue;
+        }
+        if skip > 0 && function.defaults[k].is_some() {
+            skip -= 1;
+            continue;
+        }
+        *slot = positional.next();
+    }
+    resolved
+        .into_iter()
+        .enumerate()
+        .map(|(k, arg)| {
+            arg.or_else(|| function.defaults[k].clone()).ok_or_else(|| {
+                format!(
+                    "{} Missing argument \"{}\" of function {}.",
+                    location.stringify(),
+                    param_name(params[k]),
+                    function.name
+                )
+            })
+        })
+        .collect()
+}
+
+/// `name = value` where `name` is an argument of the function.
+fn named_argument(arg: &[Token], params: &[&LiaVarName]) -> Option<(usize, Vec<Token>)> {
+    let mut meaningful = arg
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| !matches!(t, Token::Whitespace(_) | Token::Newline));
+    let (Some((_, Token::Misc(name, _))), Some((equals, Token::Misc(operator, _)))) =
+        (meaningful.next(), meaningful.next())
+    else {
+        return None;
+    };
+    if operator != "=" {
+        return None;
+    }
+    let k = params.iter().position(|p| p.matches_name(name))?;
+    Some((k, arg[equals + 1..].to_vec()))
+}
+
 fn is_defined_function(name: String, functions: Vec<Function>) -> Option<Function> {
-    for f in &functions {
+    // A function redefined with `@!name` replaces the earlier one.
+    for f in functions.iter().rev() {
         if f.name == name {
             return Some(f.clone());
         }
@@ -221,41 +814,27 @@ fn split_call_args(
     function: Option<Function>,
 ) -> Result<Vec<Arg>, String> {
     let mut args: ArgList = Vec::new();
+    let mut buffers: Vec<Vec<Token>> = Vec::new();
     let mut tokens_buffer: Vec<Token> = Vec::new();
     let mut str_args: Vec<Token> = Vec::new();
     for i in start..end {
         match &tokens[i] {
-            Token::Misc(t, _) => {
-                if t == "," {
-                    let len = tokens_buffer.len();
-                    //let whitespace = count_whitespace(&tokens_buffer, 0);
-                    append_arg(
-                        &mut args,
-                        &tokens_buffer,
-                        len,
-                        other_doc_locations,
-                        tokens,
-                        &mut str_args,
-                    )?;
-
-                    tokens_buffer = Vec::new();
-                } else {
-                    tokens_buffer.push(tokens[i].clone());
-                }
-            }
-            _ => {
-                tokens_buffer.push(tokens[i].clone());
-            }
+            Token::Misc(t, _) if t == "," => buffers.push(std::mem::take(&mut tokens_buffer)),
+            _ => tokens_buffer.push(tokens[i].clone()),
         }
     }
-    let len = tokens_buffer.len();
-    if len > 0 {
+    if !tokens_buffer.is_empty() {
+        buffers.push(tokens_buffer);
+    }
+    if let Some(function) = &function {
+        buffers = resolve_call_args(tokens[0].get_location(), function, buffers)?;
+    }
+    for buffer in &buffers {
         append_arg(
             &mut args,
-            &tokens_buffer,
-            len,
+            buffer,
+            buffer.len(),
             other_doc_locations,
-            tokens,
             &mut str_args,
         )?;
     }
@@ -287,17 +866,29 @@ fn split_call_args(
                 }
             })
             .collect();
-        let args_to_parse_in = to_typed_values(str_args)?;
-        if args.len() != b.len() {
-            return Err(format!(
-                "{} Function {} takes {} arguments, but {} were given.",
-                tokens[0].get_location().stringify(),
-                function.name,
-                b.len(),
-                args.len()
-            ));
-        }
+        let mut args_to_parse_in = to_typed_values(str_args)?;
         for i in 0..args.len() {
+            if function.defaults[i].is_some() {
+                args[i].arg_type = ArgType::Square;
+            }
+            if let (LiaVarName::Colour(_), TypedValue::String(name)) = (&b[i], &args_to_parse_in[i])
+            {
+                if let Some(colour) = Colour::named(name, &other_doc_locations.colours) {
+                    args_to_parse_in[i] = TypedValue::Colour(colour);
+                }
+            }
+            if let (LiaVarName::Size(_), TypedValue::String(text)) = (&b[i], &args_to_parse_in[i]) {
+                if let Some(size) = Size::parse(text) {
+                    args_to_parse_in[i] = TypedValue::Size(size);
+                }
+            }
+            if let TypedValue::Colour(colour) = &args_to_parse_in[i] {
+                // `#` can't be passed to a macro, so hex colours are passed as an xcolor expression.
+                args[i].arg = vec![Rc::new(Text {
+                    text: colour.stringify(),
+                })];
+                other_doc_locations.require_package("xcolor");
+            }
             if !args_to_parse_in[i].matches_declaration_type(&b[i]) {
                 return Err(format!(
                     "{} Recieved mismatched types for argument {} of function {}.",
@@ -308,6 +899,8 @@ fn split_call_args(
             }
         }
         let mut errs: Vec<String> = Vec::new();
+        let mut needs_siunitx = false;
+        let mut needs_xcolor = false;
         a.into_iter().for_each(|f| {
             if let LiaVarName::Lamda(l) = f {
                 args.push(Arg {
@@ -315,9 +908,32 @@ fn split_call_args(
                     arg: vec![Rc::new(Text {
                         text: match l.evaluate(&args_to_parse_in, "Failed for run @() expression.")
                         {
-                            Ok(r) => r.stringify(),
+                            Ok(r) => {
+                                let format = l
+                                    .number_format
+                                    .as_ref()
+                                    .or(other_doc_locations.number_format.as_ref());
+                                if format.is_some_and(|f| f.is_siunitx()) {
+                                    needs_siunitx = true;
+                                }
+                                if let TypedValue::Colour(_) = r {
+                                    needs_xcolor = true;
+                                }
+                                format_value(&r, format).unwrap_or_else(|e| {
+                                    errs.push(format!(
+                                        "{} {}",
+                                        tokens[0].get_location().stringify(),
+                                        e
+                                    ));
+                                    String::new()
+                                })
+                            }
                             Err(e) => {
-                                errs.push(e);
+                                errs.push(format!(
+                                    "{} {}",
+                                    tokens[0].get_location().stringify(),
+                                    e
+                                ));
                                 "".to_string()
                             }
                         },
@@ -325,6 +941,12 @@ fn split_call_args(
                 });
             }
         });
+        if needs_siunitx {
+            other_doc_locations.require_package("siunitx");
+        }
+        if needs_xcolor {
+            other_doc_locations.require_package("xcolor");
+        }
         if !errs.is_empty() {
             return Err(errs.join("\n"));
         }
@@ -337,28 +959,111 @@ fn append_arg(
     tokens_buffer: &Vec<Token>,
     len: usize,
     other_doc_locations: &mut CompilerGlobals,
-    tokens: TokenList,
     str_args: &mut Vec<Token>,
 ) -> Result<(), String> {
     args.push(Arg {
         arg_type: ArgType::Curly,
         arg: node_list(tokens_buffer, 0, len, other_doc_locations)?,
     });
-    let mut ws = count_whitespace(tokens, 0);
-    if ws > len - 1 {
-        ws = len - 1
-    }
+    let ws = tokens_buffer
+        .iter()
+        .position(|t| !matches!(t, Token::Whitespace(_) | Token::Newline))
+        .unwrap_or(len - 1);
     let a = &tokens_buffer[ws];
-    str_args.push(a.clone());
+    match (a, tokens_buffer.get(ws + 1)) {
+        // `0.5\textwidth` is split into a number and a command.
+        (Token::Misc(n, loc), Some(Token::TexCommand(command, _))) => {
+            str_args.push(Token::Misc(format!("{}{}", n, command), *loc))
+        }
+        // And `#1f77b4` into `#` and the digits, `-4` into `-` and `4`.
+        (Token::Misc(prefix, loc), Some(Token::Misc(digits, _)))
+            if prefix == "#" || prefix == "-" =>
+        {
+            str_args.push(Token::Misc(format!("{}{}", prefix, digits), *loc))
+        }
+        _ => str_args.push(a.clone()),
+    }
     Ok(())
 }
 
+/// Adds a token to an @() expression. `0.5\textwidth` is a single size and `@g` reads `g`.
+fn push_at_token(at_buf: &mut Vec<Token>, token: &Token) {
+    match token {
+        Token::Misc(t, loc) => at_buf.push(Token::Misc(t.clone(), *loc)),
+        Token::TexCommand(command, loc) => match at_buf.last_mut() {
+            Some(Token::Misc(n, _)) if n.parse::<f64>().is_ok() => n.push_str(command),
+            _ => at_buf.push(Token::Misc(command.clone(), *loc)),
+        },
+        Token::LiaVariable(var, loc) => at_buf.push(Token::Misc(var[1..].to_string(), *loc)),
+        _ => {}
+    }
+}
+
+/// Evaluates `@(...)` outside of a function, where only constants can be used.
+pub fn evaluate_at_expression(
+    tokens: TokenList,
+    other_doc_locations: &CompilerGlobals,
+) -> Result<(TypedValue, Option<NumberFormat>), String> {
+    let location = tokens[0].get_location();
+    let mut string_literal = StringLiteralLexer::default();
+    let mut at_buf: Vec<Token> = Vec::new();
+    for t in &tokens[2..tokens.len() - 1] {
+        if string_literal.accepts(t) {
+            at_buf.extend(string_literal.feed(t));
+        } else {
+            push_at_token(&mut at_buf, t);
+        }
+    }
+    if let Some(location) = string_literal.unclosed() {
+        return Err(format!(
+            "{} Unclosed string literal in @() expression.",
+            location.stringify()
+        ));
+    }
+    if at_buf.is_empty() {
+        return Err(format!(
+            "{} Found empty @() expression.",
+            location.stringify()
+        ));
+    }
+    let ast = parse_at_exprssion(&at_buf, vec![], other_doc_locations)?;
+    let value = ast
+        .evaluate(&vec![], "Failed to run @() expression.")
+        .map_err(|e| format!("{} {}", location.stringify(), e))?;
+    Ok((value, ast.number_format))
+}
+
+/// The text of a computed value, in its own format or the document default.
+fn computed_text(
+    value: &TypedValue,
+    format: Option<&NumberFormat>,
+    location: Location,
+    other_doc_locations: &mut CompilerGlobals,
+) -> Result<Rc<dyn Node>, String> {
+    let format = format
+        .or(other_doc_locations.number_format.as_ref())
+        .cloned();
+    if format.as_ref().is_some_and(|f| f.is_siunitx()) {
+        other_doc_locations.require_package("siunitx");
+    }
+    if let TypedValue::Colour(_) = value {
+        other_doc_locations.require_package("xcolor");
+    }
+    Ok(Rc::new(Text {
+        text: format_value(value, format.as_ref())
+            .map_err(|e| format!("{} {}", location.stringify(), e))?,
+    }))
+}
+
 fn to_typed_values(args: Vec<Token>) -> Result<Vec<TypedValue>, String> {
     let mut err: Option<String> = None;
     let args = args
         .into_iter()
         .filter_map(|a| match a {
             Token::Misc(t, _) => Some(string_to_typed_value(t).unwrap()),
+            Token::TexCommand(t, _) if Size::parse(&t).is_some() => {
+                Some(string_to_typed_value(t).unwrap())
+            }
             _ => {
                 err = Some(
                     format! {"{} Tried to pass an illegal argument.", a.get_location().stringify()},
@@ -379,34 +1084,133 @@ fn parse_var_declaration(
     terminated_by_newline: bool,
     other_doc_locations: &mut CompilerGlobals,
     trailing_whitespace: usize,
+    renew: bool,
 ) -> Result<Rc<dyn Node>, String> {
+    if let Some(colour) = colour_declaration(tokens) {
+        return Ok(define_colour(command, colour, other_doc_locations));
+    }
+    let prefix = if renew { "renew" } else { "new" };
+    let Some(arrow_pos) = find_nothing_token(tokens, "=>") else {
+        // There was no =>, so it is a const declaration.
+        return Ok(Rc::new(TexCommand {
+            command: format!("{prefix}command"),
+            args: const_declaration_args(
+                command,
+                tokens,
+                terminated_by_newline,
+                other_doc_locations,
+            )?,
+        }));
+    };
+    let spl = tokens.split_at(arrow_pos);
+    let (mut lia_variables, defaults): (Vec<LiaVarName>, Vec<Option<Vec<Token>>>) =
+        parse_fn_declaration_lhs(spl.0)?.into_iter().unzip();
+
+    let function_inner: NodeList = parse_fn_declaration_rhs(
+        &spl.1[0..(spl.1.len() - trailing_whitespace)],
+        &mut lia_variables,
+        other_doc_locations,
+    )?;
+    let len = lia_variables.len();
+    other_doc_locations.fucntions.push(Function {
+        name: command.clone(),
+        args: lia_variables,
+        defaults: defaults.clone(),
+        body: None,
+    });
+    // `\newcommand` can only make the first argument optional.
+    if defaults.iter().skip(1).any(|d| d.is_some()) {
+        other_doc_locations.require_package("xparse");
+        return Ok(Rc::new(TexCommand {
+            command: if renew {
+                "RenewDocumentCommand".to_string()
+            } else {
+                "NewDocumentCommand".to_string()
+            },
+            args: document_command_args(command, len, &defaults, function_inner),
+        }));
+    }
+    let mut args = function_declaration_args(command, len, function_inner);
+    if let Some(Some(default)) = defaults.first() {
+        args.insert(
+            2,
+            Arg {
+                arg_type: ArgType::Square,
+                arg: vec![Rc::new(Text {
+                    text: untokenise(default),
+                })],
+            },
+        );
+    }
     Ok(Rc::new(TexCommand {
-        command: "newcommand".to_string(),
-        args: match find_nothing_token(tokens, "=>") {
-            None => {
-                // There was no =>, so it is a const declaration.
-                const_declaration_args(command, tokens, terminated_by_newline, other_doc_locations)?
-            }
-            Some(arrow_pos) => {
-                let spl = tokens.split_at(arrow_pos);
-                let mut lia_variables: Vec<LiaVarName> = parse_fn_declaration_lhs(spl.0)?;
-
-                let function_inner: NodeList = parse_fn_declaration_rhs(
-                    &spl.1[0..(spl.1.len() - trailing_whitespace)],
-                    &mut lia_variables,
-                    other_doc_locations,
-                )?;
-                let len = lia_variables.len();
-                other_doc_locations.fucntions.push(Function {
-                    name: command.clone(),
-                    args: lia_variables,
-                });
-                function_declaration_args(command, len, function_inner)
-            }
-        },
+        command: format!("{prefix}command"),
+        args,
     }))
 }
 
+/// `\NewDocumentCommand{\f}{m O{default} m}{...}`, computed arguments are mandatory.
+fn document_command_args(
+    command: String,
+    argc: usize,
+    defaults: &[Option<Vec<Token>>],
+    fn_contents: NodeList,
+) -> ArgList {
+    let spec = (0..argc)
+        .map(|i| match defaults.get(i) {
+            Some(Some(default)) => format!("O{{{}}}", untokenise(default)),
+            _ => "m".to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    vec![
+        Arg {
+            arg_type: ArgType::Curly,
+            arg: vec![Rc::new(Text {
+                text: format! {"\\{command}"},
+            })],
+        },
+        Arg {
+            arg_type: ArgType::Curly,
+            arg: vec![Rc::new(Text { text: spec })],
+        },
+        Arg {
+            arg_type: ArgType::CurlyMultiline,
+            arg: fn_contents,
+        },
+    ]
+}
+
+/// `@brand = #1f77b4`, also `rgb(31, 119, 180)` or `hsl(205, 0.7, 0.41)`.
+fn colour_declaration(tokens: &TokenList) -> Option<Colour> {
+    if find_nothing_token(tokens, "=>").is_some() {
+        return None;
+    }
+    let declaration = untokenise(tokens);
+    let (_, value) = declaration.split_once('=')?;
+    Colour::parse_literal(value)
+}
+
+fn define_colour(
+    name: String,
+    colour: Colour,
+    other_doc_locations: &mut CompilerGlobals,
+) -> Rc<dyn Node> {
+    other_doc_locations.require_package("xcolor");
+    let html = colour.html();
+    other_doc_locations.colours.push(Colour {
+        name: Some(name.clone()),
+        ..colour
+    });
+    let text = |text: String| Arg {
+        arg_type: ArgType::Curly,
+        arg: vec![Rc::new(Text { text })],
+    };
+    Rc::new(TexCommand {
+        command: "definecolor".to_string(),
+        args: vec![text(name), text("HTML".to_string()), text(html)],
+    })
+}
+
 fn function_declaration_args(command: String, argc: usize, fn_contents: NodeList) -> ArgList {
     vec![
         Arg {
@@ -451,6 +1255,26 @@ fn const_declaration_args(
             text: format! {"\\{command}"},
         })],
     }];
+    // A `-D` define on the command line wins over the value in the document.
+    let define = other_doc_locations
+        .job
+        .defines
+        .iter()
+        .find(|(name, _)| *name == command);
+    if let Some((_, value)) = define {
+        ret.push(Arg {
+            arg_type: ArgType::Curly,
+            arg: vec![Rc::new(Text {
+                text: value.clone(),
+            })],
+        });
+        return Ok(ret);
+    }
+    if let Some(equals) = find_nothing_token(tokens, "=") {
+        if let Some(value) = constant_value(&tokens[equals + 1..], other_doc_locations)? {
+            other_doc_locations.constants.push((command.clone(), value));
+        }
+    }
     let equal_oper_pos = count_whitespace(tokens, 0);
     let content_pos = equal_oper_pos + count_whitespace(tokens, equal_oper_pos);
     ret.push(Arg {
@@ -469,10 +1293,45 @@ fn const_declaration_args(
     Ok(ret)
 }
 
-fn parse_fn_declaration_lhs(tokens: TokenList) -> Result<Vec<LiaVarName>, String> {
-    let mut ret: Vec<LiaVarName> = Vec::new();
+/// The value of `@name = 9.81`, `2.5cm`, `true`, plain text or `@(...)`. Other TeX is only a macro.
+fn constant_value(
+    tokens: TokenList,
+    other_doc_locations: &CompilerGlobals,
+) -> Result<Option<TypedValue>, String> {
+    let meaningful = |t: &Token| !matches!(t, Token::Whitespace(_) | Token::Newline);
+    let (Some(start), Some(end)) = (
+        tokens.iter().position(meaningful),
+        tokens.iter().rposition(meaningful),
+    ) else {
+        return Ok(None);
+    };
+    let tokens = &tokens[start..=end];
+    if let [Token::LiaVariable(var, _), Token::Misc(open, _), ..] = tokens {
+        if var == "@" && open == "(" {
+            let mut depth = BrackDepths::default();
+            let closes_at_end = tokens[1..].iter().enumerate().all(|(i, t)| {
+                depth += delta_bracket_depth(t);
+                depth.round > 0 || i == tokens.len() - 2
+            });
+            if closes_at_end {
+                return Ok(Some(evaluate_at_expression(tokens, other_doc_locations)?.0));
+            }
+        }
+    }
+    Ok(match string_to_typed_value(untokenise(tokens))? {
+        TypedValue::String(s) if s.contains(['\\', '{', '}', '@', '$']) => None,
+        value => Some(value),
+    })
+}
+
+/// An argument of a function and its default value.
+type Parameter = (LiaVarName, Option<Vec<Token>>);
+
+fn parse_fn_declaration_lhs(tokens: TokenList) -> Result<Vec<Parameter>, String> {
+    let mut ret: Vec<Parameter> = Vec::new();
     let mut brack_depth = BrackDepths::default();
     let mut sleep = 0;
+    let mut in_default = false;
     for i in 1..tokens.len() {
         if sleep > 0 {
             sleep -= 1;
@@ -480,6 +1339,23 @@ fn parse_fn_declaration_lhs(tokens: TokenList) -> Result<Vec<LiaVarName>, String
         }
         let t = &tokens[i];
         brack_depth += delta_bracket_depth(t);
+        if in_default {
+            let ends = match t {
+                Token::Misc(t, _) => {
+                    (t == "," && brack_depth.round == 1) || (t == ")" && brack_depth.round == 0)
+                }
+                _ => false,
+            };
+            if !ends {
+                let (_, default) = ret.last_mut().unwrap();
+                default.get_or_insert_with(Vec::new).push(t.clone());
+                continue;
+            }
+            in_default = false;
+            let (arg, default) = ret.last_mut().unwrap();
+            *default = default.as_deref().map(dedent).filter(|d| !d.is_empty());
+            check_default(arg, default.as_deref(), t.get_location())?;
+        }
         let mut type_annotation = "Any".to_string();
         match extract_type_annotation(tokens, i) {
             None => {}
@@ -490,15 +1366,17 @@ fn parse_fn_declaration_lhs(tokens: TokenList) -> Result<Vec<LiaVarName>, String
         }
         match t {
             Token::LiaVariable(var, loc) => {
-                ret.push(to_typed_var_name(
-                    var[1..].to_string(),
-                    type_annotation,
-                    loc,
-                )?);
+                ret.push((
+                    to_typed_var_name(var[1..].to_string(), type_annotation, loc)?,
+                    None,
+                ));
+            }
+            Token::Misc(t, _) if t == "=" && brack_depth.round == 1 && !ret.is_empty() => {
+                in_default = true;
             }
             Token::Misc(t, loc) => {
                 if t != "," && t != "=" && !is_bracket(t.chars().next().unwrap()) {
-                    ret.push(to_typed_var_name(t.clone(), type_annotation, loc)?);
+                    ret.push((to_typed_var_name(t.clone(), type_annotation, loc)?, None));
                 }
             }
             _ => {}
@@ -512,6 +1390,38 @@ fn parse_fn_declaration_lhs(tokens: TokenList) -> Result<Vec<LiaVarName>, String
     Ok(ret)
 }
 
+/// Defaults have to match the type of their argument.
+fn check_default(arg: &LiaVarName, default: Option<&[Token]>, end: Location) -> Result<(), String> {
+    let Some(default) = default else {
+        return Err(format!(
+            "{} Missing default value for argument \"{}\".",
+            end.stringify(),
+            param_name(arg)
+        ));
+    };
+    let text = untokenise(default);
+    let mut value = string_to_typed_value(text.clone())?;
+    if let (LiaVarName::Colour(_), TypedValue::String(name)) = (arg, &value) {
+        if let Some(colour) = Colour::named(name, &[]) {
+            value = TypedValue::Colour(colour);
+        }
+    }
+    if let (LiaVarName::Size(_), TypedValue::String(text)) = (arg, &value) {
+        if let Some(size) = Size::parse(text) {
+            value = TypedValue::Size(size);
+        }
+    }
+    if !value.matches_declaration_type(arg) {
+        return Err(format!(
+            "{} Default value \"{}\" of argument \"{}\" does not match its type.",
+            default[0].get_location().stringify(),
+            text,
+            param_name(arg)
+        ));
+    }
+    Ok(())
+}
+
 fn extract_type_annotation(tokens: TokenList, i: usize) -> Option<(String, usize)> {
     let ws = count_whitespace(tokens, i) + i;
     if let Token::Misc(t, _) = &tokens[if ws < tokens.len() {
@@ -540,8 +1450,7 @@ fn parse_fn_declaration_rhs(
 ) -> Result<NodeList, String> {
     let start = count_whitespace(tokens, 2) + 2;
     let mut in_at_expression = false;
-    let mut in_string_literal = false;
-    let mut string_literal_buffer = String::new();
+    let mut string_literal = StringLiteralLexer::default();
     let mut brack_depth = BrackDepths::default();
     let mut at_buf: Vec<Token> = Vec::new();
     let mut errors: Vec<String> = Vec::new();
@@ -550,57 +1459,43 @@ fn parse_fn_declaration_rhs(
         .iter()
         .filter_map(|t| -> Option<Token> {
             if in_at_expression {
+                if string_literal.accepts(t) {
+                    at_buf.extend(string_literal.feed(t));
+                    return None;
+                }
                 brack_depth += delta_bracket_depth(t);
-                if in_string_literal {
-                    match t {
-                        Token::Misc(ref t, loc) => {
-                            if t.ends_with('"') {
-                                in_string_literal = false;
-                                string_literal_buffer.push_str(t);
-                                at_buf.push(Token::Misc(string_literal_buffer.clone(), *loc));
-                                string_literal_buffer = String::new();
-                            } else {
-                                string_literal_buffer.push_str(t.as_str());
+                return match t {
+                    Token::Misc(t, loc) => {
+                        if t == ")" && brack_depth.round == 0 {
+                            in_at_expression = false;
+                            lia_variables.push(LiaVarName::Lamda(
+                                match parse_at_exprssion(
+                                    &at_buf,
+                                    lia_variables.clone(),
+                                    other_doc_locations,
+                                ) {
+                                    Ok(a) => a,
+                                    Err(e) => {
+                                        errors.push(e);
+                                        did_error = true;
+                                        Ast::default()
+                                    }
+                                },
+                            ));
+                            at_buf = Vec::new();
+                            Some(Token::Misc(format! {"#{}", lia_variables.len()}, *loc))
+                        } else {
+                            if !(t == "(" && brack_depth.round == 1) {
+                                at_buf.push(Token::Misc(t.clone(), *loc));
                             }
+                            None
                         }
-                        Token::Whitespace(ref ws) => {
-                            string_literal_buffer.push_str(ws.as_str());
-                        }
-                        _ => {}
                     }
-                    return None;
-                } else {
-                    return match t {
-                        Token::Misc(t, loc) => {
-                            if t.starts_with('"') && !(t.ends_with('"') && t.len() > 1) {
-                                in_string_literal = true;
-                                string_literal_buffer.push_str(t);
-                                return None;
-                            }
-                            if t == ")" && brack_depth.round == 0 {
-                                in_at_expression = false;
-                                lia_variables.push(LiaVarName::Lamda(
-                                    match parse_at_exprssion(&at_buf, lia_variables.clone()) {
-                                        Ok(a) => a,
-                                        Err(e) => {
-                                            errors.push(e);
-                                            did_error = true;
-                                            Ast::default()
-                                        }
-                                    },
-                                ));
-                                at_buf = Vec::new();
-                                Some(Token::Misc(format! {"#{}", lia_variables.len()}, *loc))
-                            } else {
-                                if !(t == "(" && brack_depth.round == 1) {
-                                    at_buf.push(Token::Misc(t.clone(), *loc));
-                                }
-                                None
-                            }
-                        }
-                        _ => None,
-                    };
-                }
+                    _ => {
+                        push_at_token(&mut at_buf, t);
+                        None
+                    }
+                };
             }
             match t {
                 Token::LiaVariable(var, loc) => {
@@ -619,6 +1514,13 @@ fn parse_fn_declaration_rhs(
             }
         })
         .collect();
+    if let Some(location) = string_literal.unclosed() {
+        errors.push(format!(
+            "{} Unclosed string literal in @() expression.",
+            location.stringify()
+        ));
+        did_error = true;
+    }
     if did_error {
         errors.push("Failed to parse @() expression. Aborted.".to_string());
         return Err(errors.join("\n"));
@@ -6,6 +6,7 @@ pub enum LiaVarName {
     String(String),
     Size(String),
     Colour(String),
+    Bool(String),
     Lamda(Ast),
     Any(String),
 }
@@ -17,6 +18,7 @@ impl LiaVarName {
             LiaVarName::String(s) => s == name,
             LiaVarName::Size(s) => s == name,
             LiaVarName::Colour(c) => c == name,
+            LiaVarName::Bool(b) => b == name,
             LiaVarName::Lamda(_) => false,
             LiaVarName::Any(a) => a == name,
         }
@@ -0,0 +1,124 @@
+use crate::typed_value::TypedValue;
+
+/// Absolute TeX units with their length in points.
+static ABSOLUTE_UNITS: [(&str, f64); 6] = [
+    ("pt", 1.0),
+    ("bp", 72.27 / 72.0),
+    ("pc", 12.0),
+    ("mm", 72.27 / 25.4),
+    ("cm", 72.27 / 2.54),
+    ("in", 72.27),
+];
+
+/// Units relative to the font or the page, which can't be converted to anything else.
+static RELATIVE_UNITS: [&str; 8] = [
+    "em",
+    "ex",
+    "\\textwidth",
+    "\\textheight",
+    "\\linewidth",
+    "\\columnwidth",
+    "\\paperwidth",
+    "\\paperheight",
+];
+
+/// A TeX dimension, e.g. `2.5cm` or `0.5\textwidth`.
+#[derive(Debug, Clone)]
+pub struct Size {
+    pub value: f64,
+    pub unit: String,
+}
+
+impl Size {
+    /// Parses a dimension such as `2.5cm`, `-1em` or `\linewidth`.
+    pub fn parse(s: &str) -> Option<Size> {
+        let split = s
+            .find(|c: char| c.is_alphabetic() || c == '\\')
+            .unwrap_or(s.len());
+        let (value, unit) = s.split_at(split);
+        if !is_unit(unit) {
+            return None;
+        }
+        let value = match value {
+            "" => 1.0,
+            "-" => -1.0,
+            value => value.parse::<f64>().ok()?,
+        };
+        Some(Size {
+            value,
+            unit: unit.to_string(),
+        })
+    }
+
+    fn points_per_unit(&self) -> Option<f64> {
+        ABSOLUTE_UNITS
+            .iter()
+            .find(|(u, _)| *u == self.unit)
+            .map(|(_, pt)| *pt)
+    }
+
+    /// Expresses the size in the unit of `other`.
+    pub fn convert_to(&self, other: &Size) -> Result<Size, String> {
+        if self.unit == other.unit {
+            return Ok(self.clone());
+        }
+        match (self.points_per_unit(), other.points_per_unit()) {
+            (Some(from), Some(to)) => Ok(Size {
+                value: self.value * from / to,
+                unit: other.unit.clone(),
+            }),
+            _ => Err(format!(
+                "Can't convert {} to {} in @() expression.",
+                self.unit, other.unit
+            )),
+        }
+    }
+
+    pub fn scale(&self, factor: f64) -> Size {
+        Size {
+            value: self.value * factor,
+            unit: self.unit.clone(),
+        }
+    }
+
+    pub fn stringify(&self) -> String {
+        // TeX stores dimensions in multiples of 1/65536pt, more digits are only float noise.
+        let value = format!("{:.5}", self.value);
+        let value = value.trim_end_matches('0').trim_end_matches('.');
+        let value = if value == "-0" { "0" } else { value };
+        format!("{}{}", value, self.unit)
+    }
+}
+
+fn is_unit(unit: &str) -> bool {
+    ABSOLUTE_UNITS.iter().any(|(u, _)| *u == unit) || RELATIVE_UNITS.contains(&unit)
+}
+
+/// Sum of two sizes, or difference with `sign` of `-1`, in the unit of the left side.
+pub fn add(lhs: &Size, rhs: &Size, sign: f64) -> Result<TypedValue, String> {
+    let rhs = rhs.convert_to(lhs)?;
+    Ok(TypedValue::Size(Size {
+        value: lhs.value + sign * rhs.value,
+        unit: lhs.unit.clone(),
+    }))
+}
+
+/// The ratio of two sizes is a number, e.g. `1in / 1cm` is `2.54`.
+pub fn ratio(lhs: &Size, rhs: &Size) -> Result<TypedValue, String> {
+    let lhs = lhs.convert_to(rhs)?;
+    if rhs.value == 0.0 {
+        return Err("Can't divide by a size of zero in @() expression.".to_string());
+    }
+    Ok(TypedValue::Number(lhs.value / rhs.value))
+}
+
+/// TeX can't read an infinite or `NaN` dimension, e.g. `infcm`.
+pub fn checked(size: Size) -> Result<TypedValue, String> {
+    if !size.value.is_finite() {
+        return Err(format!(
+            "The size {} in @() expression is not a valid TeX dimension.",
+            size.stringify()
+        ));
+    }
+    Ok(TypedValue::Size(size))
+}
@@ -1,4 +1,4 @@
-use crate::{token::*, utils::*};
+use crate::{grammar::symbols::is_unicode_math, token::*, utils::*};
 
 #[derive(PartialEq)]
 enum CharGroup {
@@ -7,6 +7,8 @@ enum CharGroup {
     Symbol,
     Star,
     Bracket,
+    // Always a token of its own, e.g. `$` or `α`.
+    Single,
 }
 
 fn classify_char(c: &char) -> CharGroup {
@@ -15,7 +17,9 @@ fn classify_char(c: &char) -> CharGroup {
         '(' | ')' | '{' | '}' | '[' | ']' => CharGroup::Bracket,
         '*' => CharGroup::Star,
         '=' | '>' | ',' | '#' | ':' | '%' | '<' | '~' | '!' | ';' | '+' | '-' | '/' | '^' | '`'
-        | '_' | '$' => CharGroup::Symbol,
+        | '_' => CharGroup::Symbol,
+        '$' => CharGroup::Single,
+        _ if is_unicode_math(*c) => CharGroup::Single,
         _ => CharGroup::String,
     }
 }
@@ -29,25 +33,34 @@ pub fn to_tokens(input_lia: String) -> Vec<Token> {
     let mut first_of_line = true;
     let mut line: usize = 1;
     let mut column: usize = 1;
-    let mut start_of_token = Location::default();
+    let mut start_of_token = Location { line, column };
     let mut pre_c = ' ';
     input_lia.chars().for_each(|c| {
         if c == '\r' {
             return;
         }
         if c == '\n' {
+            let token = parse_token(&current_token, first_of_line, start_of_token);
+            ret.push(token);
             line += 1;
             column = 1;
-            let token = parse_token(&current_token, first_of_line, Location { line, column });
-            ret.push(token);
             start_new_token(&mut start_of_token, line, column, &mut current_token);
             ret.push(Token::Newline);
             first_of_line = true;
             return;
         }
-        let char_group = classify_char(&c);
+        let char_group = match classify_char(&c) {
+            // Unicode is allowed in variable names.
+            CharGroup::Single if c.is_alphabetic() && current_token.starts_with('@') => {
+                CharGroup::String
+            }
+            // `@!name` redefines a command.
+            CharGroup::Symbol if c == '!' && current_token == "@" => CharGroup::String,
+            group => group,
+        };
         if (char_group != pre_char_group
             || pre_char_group == CharGroup::Bracket
+            || pre_char_group == CharGroup::Single
             || c == '\\'
             || c == '@')
             && pre_c != '\\'
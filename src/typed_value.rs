@@ -1,9 +1,21 @@
+use crate::colour::Colour;
+use crate::linear_algebra;
 use crate::parser_modules::variables::var_definition::LiaVarName;
+use crate::size::Size;
 
 #[derive(Debug, Clone)]
 pub enum TypedValue {
     Number(f64),
     String(String),
+    Vector(Vec<f64>),
+    Matrix(Vec<Vec<f64>>),
+    Size(Size),
+    Colour(Colour),
+    Bool(bool),
+    /// Rows of a data file or a JSON array.
+    List(Vec<TypedValue>),
+    /// A row of a CSV file or a JSON object, fields in the order of the file.
+    Record(Vec<(String, TypedValue)>),
 }
 
 impl TypedValue {
@@ -11,6 +23,23 @@ impl TypedValue {
         match self {
             TypedValue::Number(n) => n.to_string(),
             TypedValue::String(s) => s.clone(),
+            TypedValue::Vector(v) => {
+                linear_algebra::codegen(std::slice::from_ref(v), &|x| x.to_string())
+            }
+            TypedValue::Matrix(m) => linear_algebra::codegen(m, &|x| x.to_string()),
+            TypedValue::Size(s) => s.stringify(),
+            TypedValue::Colour(c) => c.stringify(),
+            TypedValue::Bool(b) => b.to_string(),
+            TypedValue::List(items) => items
+                .iter()
+                .map(|i| i.stringify())
+                .collect::<Vec<String>>()
+                .join(", "),
+            TypedValue::Record(fields) => fields
+                .iter()
+                .map(|(k, v)| format!("{}: {}", k, v.stringify()))
+                .collect::<Vec<String>>()
+                .join(", "),
         }
     }
 
@@ -25,6 +54,9 @@ impl TypedValue {
                 TypedValue::String(_) => true,
                 _ => false,
             },
+            LiaVarName::Size(_) => matches!(self, TypedValue::Size(_)),
+            LiaVarName::Colour(_) => matches!(self, TypedValue::Colour(_)),
+            LiaVarName::Bool(_) => matches!(self, TypedValue::Bool(_)),
             _ => false,
         }
     }
@@ -33,6 +65,13 @@ impl TypedValue {
         match self {
             TypedValue::Number(_) => "Number".to_string(),
             TypedValue::String(_) => "String".to_string(),
+            TypedValue::Vector(_) => "Vector".to_string(),
+            TypedValue::Matrix(_) => "Matrix".to_string(),
+            TypedValue::Size(_) => "Size".to_string(),
+            TypedValue::Colour(_) => "Colour".to_string(),
+            TypedValue::Bool(_) => "Bool".to_string(),
+            TypedValue::List(_) => "List".to_string(),
+            TypedValue::Record(_) => "Record".to_string(),
         }
     }
 }
@@ -138,6 +138,19 @@ pub fn count_indentation(
     }
 }
 
+/// Whether the token is the first thing on its line, ignoring indentation.
+pub fn is_start_of_line(tokens: TokenList, cursor: usize) -> bool {
+    let mut i = cursor;
+    while i > 0 {
+        match &tokens[i - 1] {
+            Token::Whitespace(_) => i -= 1,
+            Token::Newline => return true,
+            _ => return false,
+        }
+    }
+    true
+}
+
 pub fn format_error_string(message: String, location: Location) -> ParseResult {
     Err(format! {"{} {}", location.stringify(), message})
 }
@@ -1,4 +1,4 @@
-use std::fs::remove_file;
+use std::fs::{remove_file, write};
 
 use lia::compiler::*;
 use lia::utils::*;
@@ -49,16 +49,503 @@ fn equations() {
     );
 }
 
+#[test]
+fn labels() {
+    test_compilation_result(
+        "tests/labels.lia".to_string(),
+        "tests/temp_labels.tex".to_string(),
+        "tests/labels_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn labels_html() {
+    test_html_compilation_result(
+        "tests/labels.lia".to_string(),
+        "tests/temp_labels.html".to_string(),
+        "tests/labels_out.html".to_string(),
+    );
+}
+
+#[test]
+fn labels_errors() {
+    test_compilation_error(
+        "labels_undefined",
+        "See [#eq:missing].\n",
+        "Reference to undefined label \"eq:missing\".",
+    );
+    test_compilation_error(
+        "labels_duplicate",
+        "# One {#sec:a}\n# Two {#sec:a}\n",
+        "Duplicate label \"sec:a\".",
+    );
+}
+
+#[test]
+fn aligned_equations() {
+    test_compilation_result(
+        "tests/aligned_equations.lia".to_string(),
+        "tests/temp_aligned_equations.tex".to_string(),
+        "tests/aligned_equations_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn aligned_equations_errors() {
+    test_compilation_error(
+        "aligned_equations_empty_row",
+        "eqs {\n#\n}\n",
+        "2:1 Empty row in aligned equation statement.",
+    );
+    test_compilation_error(
+        "aligned_equations_label_only_row",
+        "eqs {\n    a = b\n    #eq:x\n}\n",
+        "3:5 Empty row in aligned equation statement.",
+    );
+}
+
+#[test]
+fn footnotes() {
+    test_compilation_result(
+        "tests/footnotes.lia".to_string(),
+        "tests/temp_footnotes.tex".to_string(),
+        "tests/footnotes_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn footnotes_html() {
+    test_html_compilation_result(
+        "tests/footnotes.lia".to_string(),
+        "tests/temp_footnotes.html".to_string(),
+        "tests/footnotes_out.html".to_string(),
+    );
+}
+
+#[test]
+fn footnotes_errors() {
+    test_compilation_error(
+        "footnotes_undefined",
+        "Text[^missing].\n",
+        "Reference to undefined footnote \"missing\".",
+    );
+    test_compilation_error(
+        "footnotes_duplicate",
+        "Text[^a].\n[^a]: One\n[^a]: Two\n",
+        "Duplicate footnote \"a\".",
+    );
+}
+
+#[test]
+fn markdown_blocks() {
+    test_compilation_result(
+        "tests/markdown_blocks.lia".to_string(),
+        "tests/temp_markdown_blocks.tex".to_string(),
+        "tests/markdown_blocks_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn list_styles_html() {
+    test_html_compilation_result(
+        "tests/list_styles.lia".to_string(),
+        "tests/temp_list_styles.html".to_string(),
+        "tests/list_styles_out.html".to_string(),
+    );
+}
+
+#[test]
+fn markdown_blocks_html() {
+    test_html_compilation_result(
+        "tests/markdown_blocks.lia".to_string(),
+        "tests/temp_markdown_blocks.html".to_string(),
+        "tests/markdown_blocks_out.html".to_string(),
+    );
+}
+
+#[test]
+fn list_styles() {
+    test_compilation_result(
+        "tests/list_styles.lia".to_string(),
+        "tests/temp_list_styles.tex".to_string(),
+        "tests/list_styles_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn symbols() {
+    test_compilation_result(
+        "tests/symbols.lia".to_string(),
+        "tests/temp_symbols.tex".to_string(),
+        "tests/symbols_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn unicode() {
+    test_compilation_result(
+        "tests/unicode.lia".to_string(),
+        "tests/temp_unicode.tex".to_string(),
+        "tests/unicode_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn big_operators() {
+    test_compilation_result(
+        "tests/big_operators.lia".to_string(),
+        "tests/temp_big_operators.tex".to_string(),
+        "tests/big_operators_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn big_operators_errors() {
+    test_compilation_error(
+        "big_operators_diverging_limit",
+        "@f = => {@(lim(x -> 0, log(x)))}\n@f()\n",
+        "Could not find the limit as the variable approaches 0 in @() expression.",
+    );
+    test_compilation_error(
+        "big_operators_infinite_limit",
+        "@f = => {@(lim(x -> 1 / 0, 1 / x))}\n@f()\n",
+        "Can only evaluate limits at a finite value in @() expression, found inf.",
+    );
+}
+
+#[test]
+fn scripts() {
+    test_compilation_result(
+        "tests/scripts.lia".to_string(),
+        "tests/temp_scripts.tex".to_string(),
+        "tests/scripts_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn cases() {
+    test_compilation_result(
+        "tests/cases.lia".to_string(),
+        "tests/temp_cases.tex".to_string(),
+        "tests/cases_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn matrices() {
+    test_compilation_result(
+        "tests/matrices.lia".to_string(),
+        "tests/temp_matrices.tex".to_string(),
+        "tests/matrices_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn linear_algebra() {
+    test_compilation_result(
+        "tests/linear_algebra.lia".to_string(),
+        "tests/temp_linear_algebra.tex".to_string(),
+        "tests/linear_algebra_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn linear_algebra_errors() {
+    test_compilation_error(
+        "linear_algebra_add_lengths",
+        "@f = => {@([1, 2] + [1, 2, 3])}\n@f()\n",
+        "Tried to add a vector of length 2 and a vector of length 3 in @() expression.",
+    );
+    test_compilation_error(
+        "linear_algebra_multiply_sizes",
+        "@f = => {@([1, 2; 3, 4] * [1, 2, 3])}\n@f()\n",
+        "Tried to multiply a 2x2 matrix and a vector of length 3 in @() expression.",
+    );
+    test_compilation_error(
+        "linear_algebra_determinant",
+        "@f = => {@(det([1, 2, 3; 4, 5, 6]))}\n@f()\n",
+        "Can only take the determinant of a square matrix, found a 2x3 matrix",
+    );
+}
+
+#[test]
+fn number_format() {
+    test_compilation_result(
+        "tests/number_format.lia".to_string(),
+        "tests/temp_number_format.tex".to_string(),
+        "tests/number_format_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn number_format_errors() {
+    test_compilation_error(
+        "number_format_precision",
+        "Value @(2 : .99f).\n",
+        "1:11 Invalid number format \".99f\", the precision can be at most 17.",
+    );
+    test_compilation_error(
+        "number_format_infinite",
+        "Value @(log(0)).\n",
+        "1:7 The result -inf of @() expression is not a finite number.",
+    );
+    test_compilation_error(
+        "number_format_infinite_in_function",
+        "@f = (x: num) => {@(1 / x : .2f)}\n@f(0)\n",
+        "2:1 The result inf of @() expression is not a finite number.",
+    );
+}
+
+#[test]
+fn sizes() {
+    test_compilation_result(
+        "tests/sizes.lia".to_string(),
+        "tests/temp_sizes.tex".to_string(),
+        "tests/sizes_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn colours() {
+    test_compilation_result(
+        "tests/colours.lia".to_string(),
+        "tests/temp_colours.tex".to_string(),
+        "tests/colours_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn colours_errors() {
+    test_compilation_error(
+        "colours_mix_weight",
+        "@c = @(mix(red, blue, 2))\n\\textcolor{@c}{text}\n",
+        "1:6 Argument 3 of mix() in @() expression must be between 0 and 1, found 2.",
+    );
+    test_compilation_error(
+        "colours_lighten_weight_in_function",
+        "@f = (w: num) => {@(lighten(red, w))}\n@f(-0.5)\n",
+        "2:1 Argument 2 of lighten() in @() expression must be between 0 and 1, found -0.5.",
+    );
+}
+
+#[test]
+fn booleans() {
+    test_compilation_result(
+        "tests/booleans.lia".to_string(),
+        "tests/temp_booleans.tex".to_string(),
+        "tests/booleans_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn builtins() {
+    test_compilation_result(
+        "tests/builtins.lia".to_string(),
+        "tests/temp_builtins.tex".to_string(),
+        "tests/builtins_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn builtins_errors() {
+    test_compilation_error(
+        "builtins_arity",
+        "A @(sin(1, 2)).\n",
+        "1:5 Function sin() in @() expression takes 1 arguments, but 2 were given.",
+    );
+    test_compilation_error(
+        "builtins_arity_in_function",
+        "@f = (x: num) => {@(sin(x, 2))}\n@f(2)\n",
+        "1:21 Function sin() in @() expression takes 1 arguments, but 2 were given.",
+    );
+    test_compilation_error(
+        "builtins_type_in_function",
+        "@f = (x: num) => {@(upper(x))}\n@f(2)\n",
+        "2:1 Argument 1 of upper() in @() expression must be a String, found Number.",
+    );
+}
+
+#[test]
+fn strings() {
+    test_compilation_result(
+        "tests/strings.lia".to_string(),
+        "tests/temp_strings.tex".to_string(),
+        "tests/strings_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn constants() {
+    test_compilation_result(
+        "tests/constants.lia".to_string(),
+        "tests/temp_constants.tex".to_string(),
+        "tests/constants_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn inline_functions() {
+    test_compilation_result(
+        "tests/inline_functions.lia".to_string(),
+        "tests/temp_inline_functions.tex".to_string(),
+        "tests/inline_functions_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn default_arguments() {
+    test_compilation_result(
+        "tests/default_arguments.lia".to_string(),
+        "tests/temp_default_arguments.tex".to_string(),
+        "tests/default_arguments_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn redefinitions() {
+    test_compilation_result(
+        "tests/redefinitions.lia".to_string(),
+        "tests/temp_redefinitions.tex".to_string(),
+        "tests/redefinitions_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn redefinitions_errors() {
+    test_compilation_error(
+        "redefinitions_without_bang",
+        "@greeting = Hello\n@greeting = Hi\n",
+        "\"@greeting\" is already defined at",
+    );
+}
+
+#[test]
+fn loops() {
+    test_compilation_result(
+        "tests/loops.lia".to_string(),
+        "tests/temp_loops.tex".to_string(),
+        "tests/loops_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn loops_errors() {
+    test_compilation_error(
+        "loops_too_many_iterations",
+        "for @i in 1..2000 {@i}\n",
+        "Loop over 1..2000 would run 2000 times, more than the limit of 1000.",
+    );
+    test_compilation_error(
+        "loops_too_many_nested_iterations",
+        "for @i in 1..40 {\n    for @j in 1..40 {@i@j}\n}\n",
+        "Loop would run more than the limit of 1000 times, counting nested loops.",
+    );
+}
+
+#[test]
+fn data_files() {
+    test_compilation_result(
+        "tests/data_files.lia".to_string(),
+        "tests/temp_data_files.tex".to_string(),
+        "tests/data_files_out.tex".to_string(),
+    );
+}
+
+#[test]
+fn conditionals() {
+    test_compilation_result_with_defines(
+        "tests/conditionals.lia".to_string(),
+        "tests/temp_conditionals.tex".to_string(),
+        "tests/conditionals_out.tex".to_string(),
+        &[("SOLUTIONS", "false"), ("YEAR", "2026")],
+    );
+}
+
+#[test]
+fn conditionals_solutions() {
+    test_compilation_result_with_defines(
+        "tests/conditionals.lia".to_string(),
+        "tests/temp_conditionals_solutions.tex".to_string(),
+        "tests/conditionals_solutions_out.tex".to_string(),
+        &[("SOLUTIONS", "true"), ("YEAR", "2026")],
+    );
+}
+
+#[test]
+fn conditionals_errors() {
+    test_compilation_error(
+        "conditionals_empty_else_if",
+        "@A = true\nif @A {x} else if {y}\n",
+        "Expected a condition after \"else if\".",
+    );
+    test_compilation_error(
+        "conditionals_else_after_else",
+        "@A = true\nif @A {x} else {y} else {z}\n",
+        "Found \"else\" after \"else {...}\", which must be the last branch.",
+    );
+}
+
 fn test_compilation_result(input_path: String, output_path: String, correct_output_path: String) {
-    let job = Job {
-        input_path,
-        output_path,
-        watches: false,
-        debug_printing: false,
-        chained_command: None,
-        pdflatex: false,
-        html: false,
-    };
+    compare_compilation_result(
+        Job {
+            input_path,
+            output_path,
+            watches: false,
+            debug_printing: false,
+            chained_command: None,
+            pdflatex: false,
+            html: false,
+            defines: vec![],
+        },
+        correct_output_path,
+    );
+}
+
+fn test_compilation_result_with_defines(
+    input_path: String,
+    output_path: String,
+    correct_output_path: String,
+    defines: &[(&str, &str)],
+) {
+    compare_compilation_result(
+        Job {
+            input_path,
+            output_path,
+            watches: false,
+            debug_printing: false,
+            chained_command: None,
+            pdflatex: false,
+            html: false,
+            defines: defines
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        },
+        correct_output_path,
+    );
+}
+
+fn test_html_compilation_result(
+    input_path: String,
+    output_path: String,
+    correct_output_path: String,
+) {
+    compare_compilation_result(
+        Job {
+            input_path,
+            output_path,
+            watches: false,
+            debug_printing: false,
+            chained_command: None,
+            pdflatex: false,
+            html: true,
+            defines: vec![],
+        },
+        correct_output_path,
+    );
+}
+
+fn compare_compilation_result(job: Job, correct_output_path: String) {
     match compile(job.clone()) {
         Ok(_) => {
             let output = load_utf8_file(&job.output_path).unwrap().replace("\r", "");
@@ -76,3 +563,23 @@ fn test_compilation_result(input_path: String, output_path: String, correct_outp
         }
     };
 }
+
+fn test_compilation_error(name: &str, input: &str, expected_error: &str) {
+    let input_path = format!("tests/temp_{name}.lia");
+    write(&input_path, input).unwrap();
+    let result = compile(Job {
+        input_path: input_path.clone(),
+        output_path: format!("tests/temp_{name}.tex"),
+        watches: false,
+        debug_printing: false,
+        chained_command: None,
+        pdflatex: false,
+        html: false,
+        defines: vec![],
+    });
+    remove_file(input_path).unwrap();
+    match result {
+        Ok(_) => panic!("Expected the error \"{expected_error}\", but it compiled."),
+        Err(e) => assert!(e.contains(expected_error), "Unexpected error: {e}"),
+    }
+}